@@ -0,0 +1,176 @@
+//! Read-through cache for storage queries of SubClient, keyed by '(block_hash, storage_key)'.
+use sp_core::H256 as Hash;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+#[derive(Clone, Debug)]
+struct CacheEntry {
+    // 'None' means the storage value doesn't exist at the block
+    value: Option<Vec<u8>>,
+    // entries for the latest head are dropped when a new head is seen,
+    // pinned entries are only evicted when the cache is full
+    pinned: bool,
+    tick: u64,
+}
+
+/// Bounded LRU cache of raw storage values.
+///
+/// The value of a storage key at a given block hash never changes, so entries
+/// requested by finalized, number or hash are kept until evicted by LRU.
+/// Entries requested for the latest head are invalidated once the head changes.
+#[derive(Debug)]
+pub struct QueryCache {
+    capacity: usize,
+    entries: HashMap<(Hash, Vec<u8>), CacheEntry>,
+    // tick -> key, the smallest tick is the least recently used one
+    order: BTreeMap<u64, (Hash, Vec<u8>)>,
+    tick: u64,
+    latest: Option<Hash>,
+    hits: u64,
+    misses: u64,
+}
+
+impl QueryCache {
+    pub fn new(capacity: usize) -> Self {
+        QueryCache {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            latest: None,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Return the cached value, 'Some(None)' means the storage is cached as empty.
+    pub fn get(&mut self, block: Hash, key: &[u8]) -> Option<Option<Vec<u8>>> {
+        let cache_key = (block, key.to_vec());
+        let tick = self.next_tick();
+        match self.entries.get_mut(&cache_key) {
+            Some(entry) => {
+                self.order.remove(&entry.tick);
+                entry.tick = tick;
+                self.order.insert(tick, cache_key);
+                self.hits += 1;
+                Some(entry.value.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, block: Hash, key: Vec<u8>, value: Option<Vec<u8>>, pinned: bool) {
+        if self.capacity == 0 {
+            return;
+        }
+        let cache_key = (block, key);
+        let tick = self.next_tick();
+        let pinned = match self.entries.remove(&cache_key) {
+            Some(old) => {
+                self.order.remove(&old.tick);
+                pinned || old.pinned
+            }
+            None => pinned,
+        };
+        self.order.insert(tick, cache_key.clone());
+        self.entries.insert(
+            cache_key,
+            CacheEntry {
+                value,
+                pinned,
+                tick,
+            },
+        );
+        while self.entries.len() > self.capacity {
+            match self.order.pop_first() {
+                Some((_, lru_key)) => {
+                    self.entries.remove(&lru_key);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Record the current latest head, drop unpinned entries of the previous head if it changed.
+    pub fn update_latest(&mut self, head: Hash) {
+        match self.latest {
+            Some(latest) if latest == head => {}
+            Some(latest) => {
+                let expired = self
+                    .entries
+                    .iter()
+                    .filter(|((block, _), entry)| *block == latest && !entry.pinned)
+                    .map(|(key, entry)| (key.clone(), entry.tick))
+                    .collect::<Vec<_>>();
+                for (key, tick) in expired {
+                    self.entries.remove(&key);
+                    self.order.remove(&tick);
+                }
+                self.latest = Some(head);
+            }
+            None => self.latest = Some(head),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.latest = None;
+    }
+
+    pub fn stats(&self) -> QueryCacheStats {
+        QueryCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+#[test]
+fn test_query_cache_lru_and_latest() {
+    let (head1, head2, finalized) = (
+        Hash::repeat_byte(1),
+        Hash::repeat_byte(2),
+        Hash::repeat_byte(3),
+    );
+    let mut cache = QueryCache::new(2);
+
+    cache.update_latest(head1);
+    cache.insert(head1, vec![1], Some(vec![10]), false);
+    cache.insert(finalized, vec![1], None, true);
+    assert_eq!(cache.get(head1, &[1]), Some(Some(vec![10])));
+    assert_eq!(cache.get(finalized, &[1]), Some(None));
+
+    // new head drops unpinned entries of the previous head
+    cache.update_latest(head2);
+    assert_eq!(cache.get(head1, &[1]), None);
+    assert_eq!(cache.get(finalized, &[1]), Some(None));
+
+    // least recently used entry is evicted
+    cache.insert(head2, vec![1], Some(vec![20]), false);
+    cache.insert(head2, vec![2], Some(vec![21]), false);
+    assert_eq!(cache.get(finalized, &[1]), None);
+    assert_eq!(
+        cache.stats(),
+        QueryCacheStats {
+            hits: 3,
+            misses: 2,
+            entries: 2,
+        }
+    );
+}
@@ -0,0 +1,484 @@
+//! Lifecycle tracking of cross-chain transfers of the Channel pallet, keyed by uid.
+//!
+//! A transfer goes through 'import_new_tx' (event 'NewTransaction'), signing by the committee,
+//! 'submit_tx_sign_result' (event 'SubmitTransactionSignResult'), the uid consensus record,
+//! the package of its source hash and 'sync_status' of the package, which is confirmed by the
+//! 'ExtrinsicSuccess' of the recorded 'sync_status' extrinsic.
+use crate::deepsafe::channel::events::{NewTransaction, SubmitTransactionSignResult};
+use crate::deepsafe::runtime_types::pallet_channel::types::TxStatus as TxMessageStatus;
+use crate::deepsafe::system::events::{ExtrinsicFailed, ExtrinsicSuccess};
+use crate::extrinsic::extrinsic_hash;
+use crate::query::channel::{source_hash_to_package_key, tx_messages, uid_consensus_record};
+use crate::{At, DeepSafeConfig, DeepSafeSubClient};
+use sp_core::H256 as Hash;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::SystemTime;
+use subxt::events::{EventDetails, Phase};
+use subxt::Error;
+use tokio::sync::RwLock;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TransferStage {
+    /// The tx message is imported by 'import_new_tx'.
+    Imported,
+    /// The committee members to sign the tx message are chosen.
+    Signing,
+    /// The signature is submitted by 'submit_tx_sign_result'.
+    Signed,
+    /// The uid consensus record with the source hash exists.
+    Consensus,
+    /// The source hash is packaged, the package key is known.
+    Packaged,
+    /// The package is synced by 'sync_status'.
+    Synced,
+    /// The tx message is marked abnormal by the pallet.
+    Abnormal,
+    /// The tx message is dropped by the pallet.
+    Dropped,
+}
+
+impl TransferStage {
+    /// No more stage will be reached after a final one.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            TransferStage::Synced | TransferStage::Abnormal | TransferStage::Dropped
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    pub uid: Vec<u8>,
+    pub cid: Option<u32>,
+    pub channel_id: Option<u32>,
+    // key of 'TxMessages' with 'cid'
+    pub hash: Option<Hash>,
+    pub src_chain: Option<u32>,
+    pub src_hash: Option<Vec<u8>>,
+    pub package_key: Option<Vec<u8>>,
+    pub stage: TransferStage,
+    // time when each stage is observed
+    pub timestamps: BTreeMap<TransferStage, SystemTime>,
+    // block of the event of each stage
+    pub blocks: BTreeMap<TransferStage, Hash>,
+    // extrinsics submitted for each stage, i.e. 'import_new_tx' and 'sync_status'
+    pub extrinsics: BTreeMap<TransferStage, Hash>,
+}
+
+impl Transfer {
+    fn new(uid: Vec<u8>) -> Self {
+        let now = SystemTime::now();
+        Self {
+            uid,
+            cid: None,
+            channel_id: None,
+            hash: None,
+            src_chain: None,
+            src_hash: None,
+            package_key: None,
+            stage: TransferStage::Imported,
+            timestamps: BTreeMap::from([(TransferStage::Imported, now)]),
+            blocks: BTreeMap::new(),
+            extrinsics: BTreeMap::new(),
+        }
+    }
+
+    // stages only move forward, the skipped stages are stamped with the same time
+    fn advance(&mut self, stage: TransferStage) {
+        if self.stage.is_final() || stage <= self.stage {
+            return;
+        }
+        let now = SystemTime::now();
+        match stage {
+            TransferStage::Abnormal | TransferStage::Dropped => {
+                self.timestamps.insert(stage, now);
+            }
+            _ => {
+                for skipped in [
+                    TransferStage::Signing,
+                    TransferStage::Signed,
+                    TransferStage::Consensus,
+                    TransferStage::Packaged,
+                    TransferStage::Synced,
+                ] {
+                    if skipped > self.stage && skipped <= stage {
+                        self.timestamps.entry(skipped).or_insert(now);
+                    }
+                }
+            }
+        }
+        self.stage = stage;
+    }
+}
+
+// what 'refresh' reads from the storage
+#[derive(Default)]
+struct Observed {
+    // status of 'TxMessages' and whether the signers are chosen
+    status: Option<(TxMessageStatus, bool)>,
+    // source of the uid consensus record
+    src: Option<(u32, Vec<u8>)>,
+    package_key: Option<Vec<u8>>,
+}
+
+impl Observed {
+    // 'Abnormal' and 'Dropped' by the pallet take precedence over the inferred stages,
+    // 'Synced' is only confirmed by the 'sync_status' extrinsic
+    fn stage(&self) -> Option<TransferStage> {
+        let signing = match &self.status {
+            Some((TxMessageStatus::Abnormal, _)) => return Some(TransferStage::Abnormal),
+            Some((TxMessageStatus::Drop, _)) => return Some(TransferStage::Dropped),
+            Some((TxMessageStatus::Unsigned, false)) | None => None,
+            Some((TxMessageStatus::Unsigned, true)) => Some(TransferStage::Signing),
+            Some((TxMessageStatus::Finished, _)) => Some(TransferStage::Signed),
+        };
+        if self.package_key.is_some() {
+            Some(TransferStage::Packaged)
+        } else if self.src.is_some() {
+            Some(TransferStage::Consensus)
+        } else {
+            signing
+        }
+    }
+}
+
+/// Follows cross-chain transfers by the Channel events and storage, answers where a
+/// transfer is by its uid or the hash of its tx message.
+#[derive(Clone, Debug, Default)]
+pub struct TransferTracker {
+    transfers: Arc<RwLock<HashMap<Vec<u8>, Transfer>>>,
+    // tx message hash -> uid
+    hashes: Arc<RwLock<HashMap<Hash, Vec<u8>>>>,
+}
+
+impl TransferTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track the transfer imported by 'import_new_tx', 'extrinsic' is the hash of the submitted tx.
+    pub async fn track(&self, channel_id: u32, cid: u32, uid: Vec<u8>, extrinsic: Option<Hash>) {
+        let mut transfers = self.transfers.write().await;
+        let transfer = transfers
+            .entry(uid.clone())
+            .or_insert_with(|| Transfer::new(uid));
+        transfer.channel_id = Some(channel_id);
+        transfer.cid = Some(cid);
+        if let Some(extrinsic) = extrinsic {
+            transfer
+                .extrinsics
+                .insert(TransferStage::Imported, extrinsic);
+        }
+    }
+
+    /// Record the submitted 'sync_status' of the package for the transfers in it, they are
+    /// moved to 'Synced' by 'handle_events' once the extrinsic succeeds.
+    pub async fn record_sync_status(&self, package_key: &[u8], extrinsic: Hash) {
+        for transfer in self.transfers.write().await.values_mut() {
+            if transfer.package_key.as_deref() == Some(package_key) {
+                transfer.extrinsics.insert(TransferStage::Synced, extrinsic);
+            }
+        }
+    }
+
+    /// Handle the events of the block, i.e. from the handler of 'EventWatcher'. The uid of a new
+    /// tx message is read from 'TxMessages' at the block, and the result of the recorded
+    /// 'sync_status' extrinsics is read from the system events of the block.
+    pub async fn handle_events(
+        &self,
+        sub_client: &DeepSafeSubClient,
+        block_hash: Hash,
+        events: &[EventDetails<DeepSafeConfig>],
+    ) -> Result<(), Error> {
+        for event in events {
+            if let Some(NewTransaction(cid, _, _, hash)) = event.as_event::<NewTransaction>()? {
+                let message = match tx_messages(sub_client, cid, hash, At::Hash(block_hash)).await?
+                {
+                    Some(message) => message,
+                    None => continue,
+                };
+                let uid = message.txsource.uid;
+                let mut transfers = self.transfers.write().await;
+                let transfer = transfers
+                    .entry(uid.clone())
+                    .or_insert_with(|| Transfer::new(uid.clone()));
+                transfer.cid = Some(cid);
+                transfer.hash = Some(hash);
+                transfer.blocks.insert(TransferStage::Imported, block_hash);
+                self.hashes.write().await.insert(hash, uid);
+            } else if let Some(SubmitTransactionSignResult(_, _, _, hash)) =
+                event.as_event::<SubmitTransactionSignResult>()?
+            {
+                let uid = match self.hashes.read().await.get(&hash) {
+                    Some(uid) => uid.clone(),
+                    None => continue,
+                };
+                if let Some(transfer) = self.transfers.write().await.get_mut(&uid) {
+                    transfer.blocks.insert(TransferStage::Signed, block_hash);
+                    transfer.advance(TransferStage::Signed);
+                }
+            }
+        }
+        if !self.has_unconfirmed_sync().await {
+            return Ok(());
+        }
+        let block = match sub_client
+            .client
+            .read()
+            .await
+            .rpc()
+            .block(Some(block_hash))
+            .await?
+        {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+        // (extrinsic hash, succeeded)
+        let mut results = Vec::new();
+        for event in events {
+            let index = match event.phase() {
+                Phase::ApplyExtrinsic(index) => index as usize,
+                _ => continue,
+            };
+            let succeeded = if event.as_event::<ExtrinsicSuccess>()?.is_some() {
+                true
+            } else if event.as_event::<ExtrinsicFailed>()?.is_some() {
+                false
+            } else {
+                continue;
+            };
+            if let Some(extrinsic) = block.block.extrinsics.get(index) {
+                results.push((extrinsic_hash(&extrinsic.0), succeeded));
+            }
+        }
+        self.confirm_sync_status(block_hash, &results).await;
+        Ok(())
+    }
+
+    async fn has_unconfirmed_sync(&self) -> bool {
+        self.transfers.read().await.values().any(|transfer| {
+            !transfer.stage.is_final() && transfer.extrinsics.contains_key(&TransferStage::Synced)
+        })
+    }
+
+    // move the transfers to 'Synced' by the succeeded 'sync_status', a failed one is forgotten
+    // so that it could be submitted again
+    async fn confirm_sync_status(&self, block_hash: Hash, results: &[(Hash, bool)]) {
+        for transfer in self.transfers.write().await.values_mut() {
+            let extrinsic = match transfer.extrinsics.get(&TransferStage::Synced) {
+                Some(extrinsic) if !transfer.stage.is_final() => *extrinsic,
+                _ => continue,
+            };
+            match results.iter().find(|(hash, _)| *hash == extrinsic) {
+                Some((_, true)) => {
+                    transfer.blocks.insert(TransferStage::Synced, block_hash);
+                    transfer.advance(TransferStage::Synced);
+                }
+                Some((_, false)) => {
+                    transfer.extrinsics.remove(&TransferStage::Synced);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Read the storage of the transfer at 'at_block' and move it to the latest stage,
+    /// 'None' if the uid isn't tracked.
+    pub async fn refresh(
+        &self,
+        sub_client: &DeepSafeSubClient,
+        uid: &[u8],
+        at_block: At,
+    ) -> Result<Option<Transfer>, Error> {
+        let transfer = match self.get(uid).await {
+            Some(transfer) => transfer,
+            None => return Ok(None),
+        };
+        let cid = match transfer.cid {
+            Some(cid) => cid,
+            None => return Ok(Some(transfer)),
+        };
+        let mut observed = Observed::default();
+        if let Some(hash) = transfer.hash {
+            if let Some(message) = tx_messages(sub_client, cid, hash, at_block).await? {
+                observed.status = Some((message.status, !message.choose_index.is_empty()));
+            }
+        }
+        let src = match (&transfer.src_chain, &transfer.src_hash) {
+            (Some(src_chain), Some(src_hash)) => Some((*src_chain, src_hash.clone())),
+            _ => uid_consensus_record(sub_client, cid, uid.to_vec(), at_block)
+                .await?
+                .map(|record| (record.src_chain, record.src_hash)),
+        };
+        if let Some((src_chain, src_hash)) = src {
+            observed.package_key = match &transfer.package_key {
+                Some(package_key) => Some(package_key.clone()),
+                None => {
+                    source_hash_to_package_key(sub_client, src_chain, src_hash.clone(), at_block)
+                        .await?
+                }
+            };
+            observed.src = Some((src_chain, src_hash));
+        }
+        Ok(self.apply(uid, observed).await)
+    }
+
+    // move the transfer to the stage observed by 'refresh'
+    async fn apply(&self, uid: &[u8], observed: Observed) -> Option<Transfer> {
+        let mut transfers = self.transfers.write().await;
+        let transfer = transfers.get_mut(uid)?;
+        if let Some(stage) = observed.stage() {
+            transfer.advance(stage);
+        }
+        if let Some((src_chain, src_hash)) = observed.src {
+            transfer.src_chain = Some(src_chain);
+            transfer.src_hash = Some(src_hash);
+        }
+        if observed.package_key.is_some() {
+            transfer.package_key = observed.package_key;
+        }
+        Some(transfer.clone())
+    }
+
+    pub async fn get(&self, uid: &[u8]) -> Option<Transfer> {
+        self.transfers.read().await.get(uid).cloned()
+    }
+
+    /// The transfer by the hash of its tx message.
+    pub async fn get_by_hash(&self, hash: &Hash) -> Option<Transfer> {
+        let uid = self.hashes.read().await.get(hash)?.clone();
+        self.get(&uid).await
+    }
+
+    /// All tracked transfers without a final stage.
+    pub async fn pending(&self) -> Vec<Transfer> {
+        self.transfers
+            .read()
+            .await
+            .values()
+            .filter(|transfer| !transfer.stage.is_final())
+            .cloned()
+            .collect()
+    }
+
+    /// Remove all transfers with a final stage, return the number of removed.
+    pub async fn prune_finished(&self) -> usize {
+        let mut transfers = self.transfers.write().await;
+        let before = transfers.len();
+        transfers.retain(|_, transfer| !transfer.stage.is_final());
+        self.hashes
+            .write()
+            .await
+            .retain(|_, uid| transfers.contains_key(uid));
+        before - transfers.len()
+    }
+}
+
+#[tokio::test]
+async fn test_transfer_stages() {
+    let tracker = TransferTracker::new();
+    let uid = vec![1u8; 32];
+    tracker
+        .track(1, 2, uid.clone(), Some(Hash::repeat_byte(1)))
+        .await;
+    let transfer = tracker.get(&uid).await.unwrap();
+    assert_eq!(transfer.stage, TransferStage::Imported);
+    assert_eq!(transfer.cid, Some(2));
+
+    let observed = Observed {
+        status: Some((TxMessageStatus::Finished, true)),
+        src: Some((3, vec![4])),
+        package_key: None,
+    };
+    let transfer = tracker.apply(&uid, observed).await.unwrap();
+    assert_eq!(transfer.stage, TransferStage::Consensus);
+    assert_eq!(transfer.src_chain, Some(3));
+    assert!(transfer.timestamps.contains_key(&TransferStage::Signed));
+
+    // the package is cleared from storage, but it's not synced until confirmed
+    let observed = Observed {
+        status: None,
+        src: Some((3, vec![4])),
+        package_key: Some(vec![9]),
+    };
+    let transfer = tracker.apply(&uid, observed).await.unwrap();
+    assert_eq!(transfer.stage, TransferStage::Packaged);
+    tracker.record_sync_status(&[9], Hash::repeat_byte(2)).await;
+    assert!(tracker.has_unconfirmed_sync().await);
+
+    // a failed 'sync_status' is forgotten
+    tracker
+        .confirm_sync_status(Hash::repeat_byte(7), &[(Hash::repeat_byte(2), false)])
+        .await;
+    let transfer = tracker.get(&uid).await.unwrap();
+    assert_eq!(transfer.stage, TransferStage::Packaged);
+    assert!(transfer.extrinsics.get(&TransferStage::Synced).is_none());
+    assert_eq!(tracker.prune_finished().await, 0);
+
+    tracker.record_sync_status(&[9], Hash::repeat_byte(3)).await;
+    tracker
+        .confirm_sync_status(
+            Hash::repeat_byte(8),
+            &[(Hash::repeat_byte(5), true), (Hash::repeat_byte(3), true)],
+        )
+        .await;
+    let transfer = tracker.get(&uid).await.unwrap();
+    assert_eq!(transfer.stage, TransferStage::Synced);
+    assert_eq!(
+        transfer.blocks.get(&TransferStage::Synced),
+        Some(&Hash::repeat_byte(8))
+    );
+    assert!(tracker.pending().await.is_empty());
+    assert_eq!(tracker.prune_finished().await, 1);
+    assert!(tracker.get(&uid).await.is_none());
+}
+
+#[test]
+fn test_observed_stage() {
+    let observed = |status, src: Option<(u32, Vec<u8>)>, package_key: Option<Vec<u8>>| Observed {
+        status,
+        src,
+        package_key,
+    };
+    assert_eq!(
+        observed(Some((TxMessageStatus::Unsigned, false)), None, None).stage(),
+        None
+    );
+    assert_eq!(
+        observed(Some((TxMessageStatus::Unsigned, true)), None, None).stage(),
+        Some(TransferStage::Signing)
+    );
+    assert_eq!(
+        observed(
+            Some((TxMessageStatus::Finished, true)),
+            Some((1, vec![2])),
+            None
+        )
+        .stage(),
+        Some(TransferStage::Consensus)
+    );
+    assert_eq!(
+        observed(None, Some((1, vec![2])), Some(vec![3])).stage(),
+        Some(TransferStage::Packaged)
+    );
+    assert_eq!(
+        observed(
+            Some((TxMessageStatus::Abnormal, true)),
+            Some((1, vec![2])),
+            Some(vec![3])
+        )
+        .stage(),
+        Some(TransferStage::Abnormal)
+    );
+    assert_eq!(
+        observed(
+            Some((TxMessageStatus::Drop, true)),
+            Some((1, vec![2])),
+            None
+        )
+        .stage(),
+        Some(TransferStage::Dropped)
+    );
+}
@@ -1,26 +1,45 @@
-use crate::deepsafe::runtime_types::ethereum::transaction::{
-    EIP1559Transaction, TransactionAction, TransactionV2 as EvmTransaction,
+use crate::cache::{QueryCache, QueryCacheStats};
+use crate::compatibility::{compare_metadata, CompatibilityReport, COMPILED_METADATA};
+use crate::deepsafe::runtime_types::ethereum::transaction::TransactionV2 as EvmTransaction;
+use crate::evm::abi::{decode_output, revert_reason, Codec};
+use crate::evm::fee::EvmFeeEstimator;
+use crate::evm::nonce::{replacement, EvmNonceTracker, PendingEvmTx, REPLACEMENT_BUMP_PERCENT};
+use crate::evm::tx::{to_runtime_transaction, EvmTxBuilder};
+use crate::extrinsic::{decode_extrinsic, DecodedExtrinsic};
+use crate::offline::OfflineParams;
+use crate::storage::{
+    decode_storage_default, decode_storage_value, proof::StorageProofBundle, storage_key_bytes,
+    StorageBatch, StorageDiff, StorageDiffBatch,
 };
+use crate::tx_tracker::{call_names, TxTracker};
+use crate::types::{DryRunOutcome, EvmCallRequest, RuntimeDispatchInfo, TxValidity};
 use anyhow::Result;
-use codec::{Compact, Encode};
+use codec::{Compact, Decode, Encode};
 use def_node_primitives::AccountId20;
-use sp_core::H256 as Hash;
-use std::collections::HashMap;
+use sp_core::{H160, H256 as Hash};
+use sp_runtime::transaction_validity::{TransactionSource, TransactionValidity};
+use sp_runtime::{ApplyExtrinsicResult, DispatchError};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
 use std::sync::Arc;
-use std::time::Instant;
-use subxt::config::extrinsic_params::BaseExtrinsicParamsBuilder;
+use std::time::{Instant, SystemTime};
+use subxt::blocks::ExtrinsicEvents;
+use subxt::config::extrinsic_params::{BaseExtrinsicParamsBuilder, Era};
 use subxt::config::{
-    polkadot::PolkadotExtrinsicParams,
+    polkadot::{PolkadotExtrinsicParams, PolkadotExtrinsicParamsBuilder},
     substrate::{BlakeTwo256, SubstrateHeader},
 };
+use subxt::rpc::types::{RuntimeVersion, StorageChangeSet};
 use subxt::tx::{Signer, SubmittableExtrinsic};
 use subxt::{
     error::RpcError,
+    rpc_params,
     storage::{address::Yes, StorageAddress, StorageKey},
     tx::{DeepSafeSigner, SecretKey, TxPayload, TxProgress},
-    Config, Error, JsonRpseeError, OnlineClient,
+    Config, Error, JsonRpseeError, Metadata, OnlineClient,
 };
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc::Receiver, RwLock};
+use tokio::task::JoinHandle;
 
 #[derive(Clone, Debug)]
 pub enum DeepSafeConfig {}
@@ -36,6 +55,142 @@ impl Config for DeepSafeConfig {
     type ExtrinsicParams = PolkadotExtrinsicParams<Self>;
 }
 
+/// Block to query storage at.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum At {
+    /// The best block, resolved by the node when the query is handled.
+    #[default]
+    Latest,
+    /// The latest finalized block.
+    Finalized,
+    /// Block with the given number.
+    Number(u32),
+    /// Block with the given hash.
+    Hash(Hash),
+}
+
+impl From<Hash> for At {
+    fn from(hash: Hash) -> Self {
+        At::Hash(hash)
+    }
+}
+
+impl From<Option<Hash>> for At {
+    fn from(hash: Option<Hash>) -> Self {
+        hash.map(At::Hash).unwrap_or(At::Latest)
+    }
+}
+
+impl From<u32> for At {
+    fn from(number: u32) -> Self {
+        At::Number(number)
+    }
+}
+
+/// When a signed tx submission returns.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WaitFor {
+    /// Return the tx hash once the tx is accepted by the tx pool.
+    None,
+    /// Wait until the tx is included in a block and dispatched successfully.
+    #[default]
+    InBlock,
+    /// Wait until the block including the tx is finalized and the tx dispatched successfully.
+    Finalized,
+}
+
+/// Per-call parameters of a signed tx.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubmitOptions {
+    /// Mortal period in blocks counted from the latest finalized block, immortal if 'None'.
+    pub mortality: Option<u64>,
+    /// Initial tip of the tx, re-submission for a stuck nonce adds more tip on top of it.
+    pub tip: u128,
+    /// Explicit nonce, the nonce is managed by the client if 'None'.
+    pub nonce: Option<u32>,
+    /// Wait mode of the tx, the default of the call if 'None', which is 'WaitFor::InBlock'
+    /// unless the call documents otherwise.
+    pub wait_for: Option<WaitFor>,
+}
+
+impl SubmitOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mortal(mut self, period: u64) -> Self {
+        self.mortality = Some(period);
+        self
+    }
+
+    pub fn tip(mut self, tip: u128) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn nonce(mut self, nonce: u32) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn wait_for(mut self, wait_for: WaitFor) -> Self {
+        self.wait_for = Some(wait_for);
+        self
+    }
+}
+
+/// Number of storage keys fetched in one page when walking a storage prefix.
+pub const STORAGE_PAGE_SIZE: u32 = 512;
+
+/// Number of 'block number -> block hash' mappings kept by 'BlockHashCache'.
+pub const BLOCK_HASH_CACHE_SIZE: usize = 1024;
+
+/// Recently resolved block hashes, only finalized blocks are cached because
+/// the hash of an unfinalized block number could be changed by re-org.
+#[derive(Debug, Default)]
+pub struct BlockHashCache {
+    // highest finalized block number known by the cache
+    pub finalized: u32,
+    pub hashes: BTreeMap<u32, Hash>,
+}
+
+impl BlockHashCache {
+    pub fn insert(&mut self, number: u32, hash: Hash) {
+        self.hashes.insert(number, hash);
+        // evict the oldest blocks first
+        while self.hashes.len() > BLOCK_HASH_CACHE_SIZE {
+            self.hashes.pop_first();
+        }
+    }
+}
+
+pub type RuntimeUpgradeHook = Box<dyn Fn(&RuntimeVersion) + Send + Sync>;
+
+/// Handle of the runtime version subscription started by 'watch_runtime_upgrade'.
+#[derive(Clone)]
+pub struct RuntimeWatchHandle {
+    task: Arc<std::sync::Mutex<Option<JoinHandle<()>>>>,
+    watched: Arc<AtomicBool>,
+}
+
+impl RuntimeWatchHandle {
+    pub fn is_running(&self) -> bool {
+        self.task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map_or(false, |task| !task.is_finished())
+    }
+
+    /// Stop the subscription, the runtime version is checked by rpc before every call again.
+    pub fn stop(&self) {
+        if let Some(task) = self.task.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            task.abort();
+        }
+        self.watched.store(false, AtomicOrdering::SeqCst);
+    }
+}
+
 #[derive(Clone)]
 pub struct SubClient<C: Config, P: Signer<C> + Clone> {
     pub ws_url: String,
@@ -44,14 +199,36 @@ pub struct SubClient<C: Config, P: Signer<C> + Clone> {
     pub inner_nonce: Arc<RwLock<u32>>,
     // number of cache, will re-submit call if 'call_cache' length up to it.
     pub cache_size_for_call: u32,
-    // call cache with target nonce, ture value for 'bool' param means the tx is submitted by evm, 'Vec<u8>' is input for evm tx, 'u128' is tx tip for priority.
-    pub call_cache:
-        Arc<RwLock<HashMap<u32, (Box<dyn TxPayload + Send + Sync>, bool, Vec<u8>, u128)>>>,
+    // call cache of signed substrate extrinsics with target nonce, 'u128' is tx tip for priority,
+    // evm tx are kept by 'evm_nonce_tracker' instead.
+    pub call_cache: Arc<RwLock<HashMap<u32, (Box<dyn TxPayload + Send + Sync>, u128)>>>,
     // milliseconds, default 10000 milllis(10 seconds)
     pub warn_time: u128,
+    // finalized block number -> block hash, used to resolve 'At::Number'
+    pub block_hash_cache: Arc<RwLock<BlockHashCache>>,
+    // opt-in read-through cache for storage queries, disabled by default
+    pub query_cache: Option<Arc<RwLock<QueryCache>>>,
+    // true while the runtime version subscription is alive, then the runtime version
+    // needn't to be checked by rpc before every call.
+    pub runtime_watched: Arc<AtomicBool>,
+    // task of the runtime version subscription, see 'watch_runtime_upgrade'
+    pub runtime_watcher: Arc<std::sync::Mutex<Option<JoinHandle<()>>>>,
+    // called after the client is rebuilt for a runtime upgrade
+    pub runtime_upgrade_hooks: Arc<RwLock<Vec<RuntimeUpgradeHook>>>,
+    // increased by every 'rebuild_client', subscriptions resubscribe when it changes
+    pub client_generation: Arc<AtomicU64>,
+    // refuse to submit signed tx if the signer can't cover the estimated fee, disabled by default
+    pub fee_guard: bool,
+    // records and follows every submitted tx if set
+    pub tx_tracker: Option<TxTracker>,
+    // estimates the fees and gas of evm tx if set, otherwise the fixed defaults are used
+    pub evm_fee_estimator: Option<EvmFeeEstimator>,
+    // nonce and pending tx of signed evm tx, separated from 'call_cache'
+    pub evm_nonce_tracker: EvmNonceTracker,
 }
 
 impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
+    /// The runtime upgrade subscription is started, stop it by 'runtime_watch_handle'.
     pub async fn new(
         url: &str,
         id: &str,
@@ -71,7 +248,7 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
             .account_nonce(signer.account_id())
             .await
             .unwrap();
-        SubClient {
+        let sub_client = SubClient {
             ws_url: url.to_string(),
             signer: Some(signer),
             client: Arc::new(RwLock::new(subxt_client)),
@@ -79,9 +256,22 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
             cache_size_for_call: cache_size_for_call.unwrap_or(10),
             call_cache: Arc::new(RwLock::new(HashMap::new())),
             warn_time: warn_time.unwrap_or(10000),
-        }
+            block_hash_cache: Arc::new(RwLock::new(BlockHashCache::default())),
+            query_cache: None,
+            runtime_watched: Arc::new(AtomicBool::new(false)),
+            runtime_watcher: Arc::new(std::sync::Mutex::new(None)),
+            runtime_upgrade_hooks: Arc::new(RwLock::new(Vec::new())),
+            client_generation: Arc::new(AtomicU64::new(0)),
+            fee_guard: false,
+            tx_tracker: None,
+            evm_fee_estimator: None,
+            evm_nonce_tracker: EvmNonceTracker::new(),
+        };
+        sub_client.watch_runtime_upgrade();
+        sub_client
     }
 
+    /// The runtime upgrade subscription is started, stop it by 'runtime_watch_handle'.
     pub async fn new_from_ecdsa_sk(
         url: String,
         sk: Option<String>,
@@ -106,7 +296,7 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         } else {
             None
         };
-        Ok(SubClient {
+        let sub_client = SubClient {
             ws_url: url,
             signer,
             client: Arc::new(RwLock::new(subxt_client)),
@@ -114,7 +304,19 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
             cache_size_for_call: cache_size_for_call.unwrap_or(10),
             call_cache: Arc::new(RwLock::new(HashMap::new())),
             warn_time: warn_time.unwrap_or(10000),
-        })
+            block_hash_cache: Arc::new(RwLock::new(BlockHashCache::default())),
+            query_cache: None,
+            runtime_watched: Arc::new(AtomicBool::new(false)),
+            runtime_watcher: Arc::new(std::sync::Mutex::new(None)),
+            runtime_upgrade_hooks: Arc::new(RwLock::new(Vec::new())),
+            client_generation: Arc::new(AtomicU64::new(0)),
+            fee_guard: false,
+            tx_tracker: None,
+            evm_fee_estimator: None,
+            evm_nonce_tracker: EvmNonceTracker::new(),
+        };
+        sub_client.watch_runtime_upgrade();
+        Ok(sub_client)
     }
 
     pub async fn submit_extrinsic_with_signer_and_watch<Call: TxPayload + 'static + Send + Sync>(
@@ -122,9 +324,47 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         call: Call,
         nonce: Option<u32>,
     ) -> Result<Hash, Error> {
+        self.submit_extrinsic_with_options(
+            call,
+            SubmitOptions {
+                nonce,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Sign and submit 'call' with the era, tip and nonce of 'options', then wait as 'options.wait_for'.
+    pub async fn submit_extrinsic_with_options<Call: TxPayload + 'static + Send + Sync>(
+        &self,
+        call: Call,
+        options: SubmitOptions,
+    ) -> Result<Hash, Error> {
+        match options.wait_for.unwrap_or_default() {
+            WaitFor::None => self.submit_signed_without_watch(call, options).await,
+            WaitFor::InBlock | WaitFor::Finalized => self
+                .submit_extrinsic_with_signer_and_watch_events(call, options)
+                .await
+                .map(|events| events.extrinsic_hash()),
+        }
+    }
+
+    /// Same as 'submit_extrinsic_with_options', but return all events of the successful
+    /// extrinsic, i.e. to decode results of a batch call. 'WaitFor::None' is handled as
+    /// 'WaitFor::InBlock' because events are only available after the tx is in block.
+    pub async fn submit_extrinsic_with_signer_and_watch_events<
+        Call: TxPayload + 'static + Send + Sync,
+    >(
+        &self,
+        call: Call,
+        options: SubmitOptions,
+    ) -> Result<ExtrinsicEvents<DeepSafeConfig>, Error> {
         let call = Box::new(call);
         let timer = Instant::now();
         self.check_client_runtime_version_and_update().await?;
+        if self.fee_guard {
+            self.ensure_fee_affordable(call.as_ref()).await?;
+        }
 
         let mut inner_nonce = self.inner_nonce.write().await;
         let mut call_cache = self.call_cache.write().await;
@@ -135,7 +375,7 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
             .ok_or_else(|| Error::Other("empty sk to sign and submit tx".to_string()))?;
         let account_id = signer.account_id();
 
-        let target_nonce = if let Some(nonce) = nonce {
+        let target_nonce = if let Some(nonce) = options.nonce {
             nonce
         } else {
             let chain_nonce = client.tx().account_nonce(account_id).await? as u32;
@@ -157,17 +397,13 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
                 if *inner_nonce - chain_nonce > self.cache_size_for_call {
                     log::warn!(target: "subxt", "Some errors occurred to nonce inner {}, chain {}", *inner_nonce, chain_nonce);
                     for key in chain_nonce..*inner_nonce {
-                        if let Some((inner_call, by_evm, _, _)) = call_cache.get(&key) {
-                            let tx = if *by_evm {
-                                client.tx().create_unsigned(inner_call)?
-                            } else {
-                                client.tx().create_signed_with_nonce(
-                                    inner_call,
-                                    signer,
-                                    key,
-                                    Default::default(),
-                                )?
-                            };
+                        if let Some((inner_call, _)) = call_cache.get(&key) {
+                            let tx = client.tx().create_signed_with_nonce(
+                                inner_call,
+                                signer,
+                                key,
+                                Default::default(),
+                            )?;
                             let tx_hash = tx.submit().await;
                             log::warn!(target: "subxt", "re-submit call with nonce: {}, res: {:?}", key, tx_hash);
                         } else {
@@ -183,22 +419,38 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
                 &call,
                 signer,
                 target_nonce,
-                Default::default(),
+                self.extrinsic_params(&client, &options).await?,
             )?;
-        let tx_hash = match tx.submit_and_watch().await?.wait_for_in_block().await {
+        let progress = tx.submit_and_watch().await?;
+        let finalized = options.wait_for == Some(WaitFor::Finalized);
+        let in_block = match &self.tx_tracker {
+            Some(tracker) => {
+                tracker
+                    .track_and_wait(
+                        progress,
+                        Some(target_nonce),
+                        call_names(call.as_ref()),
+                        finalized,
+                    )
+                    .await
+            }
+            None if finalized => progress.wait_for_finalized().await,
+            None => progress.wait_for_in_block().await,
+        };
+        let events = match in_block {
             Ok(tx) => {
                 log::debug!(target: "subxt::nonce", "inner_nonce {}, insert cache for nonce: {}", target_nonce + 1, target_nonce);
                 *inner_nonce = target_nonce + 1;
                 // update call_cache
-                call_cache.insert(target_nonce, (call, false, vec![], 0));
-                tx.wait_for_success().await?.extrinsic_hash()
+                call_cache.insert(target_nonce, (call, options.tip));
+                tx.wait_for_success().await?
             }
             Err(e) => return Err(e),
         };
         if timer.elapsed().as_millis() > self.warn_time {
             log::warn!(target: "subxt", "submit_extrinsic_with_signer_and_watch exceed warn_time: {} millis", timer.elapsed().as_millis());
         }
-        Ok(tx_hash)
+        Ok(events)
     }
 
     pub async fn submit_extrinsic_with_signer_without_watch<
@@ -207,10 +459,29 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         &self,
         call: Call,
         nonce: Option<u32>,
+    ) -> Result<Hash, Error> {
+        self.submit_extrinsic_with_options(
+            call,
+            SubmitOptions {
+                nonce,
+                wait_for: Some(WaitFor::None),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    async fn submit_signed_without_watch<Call: TxPayload + 'static + Send + Sync>(
+        &self,
+        call: Call,
+        options: SubmitOptions,
     ) -> Result<Hash, Error> {
         let call = Box::new(call);
         let timer = Instant::now();
         self.check_client_runtime_version_and_update().await?;
+        if self.fee_guard {
+            self.ensure_fee_affordable(call.as_ref()).await?;
+        }
 
         let mut inner_nonce = self.inner_nonce.write().await;
         let mut call_cache = self.call_cache.write().await;
@@ -221,7 +492,7 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
             .ok_or_else(|| Error::Other("empty sk to sign and submit tx".to_string()))?;
         let account_id = signer.account_id();
 
-        let target_nonce = if let Some(nonce) = nonce {
+        let target_nonce = if let Some(nonce) = options.nonce {
             nonce
         } else {
             let chain_nonce = client.tx().account_nonce(account_id).await? as u32;
@@ -242,28 +513,13 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
                 if *inner_nonce - chain_nonce >= self.cache_size_for_call {
                     log::warn!(target: "subxt", "Some errors occurred to nonce inner {}, chain {}", *inner_nonce, chain_nonce);
                     for key in chain_nonce..*inner_nonce {
-                        if let Some((inner_call, by_evm, input, tip)) = call_cache.get_mut(&key) {
-                            let tx = if *by_evm {
-                                let mut eip1995_tx =
-                                    <ethereum::EIP1559Transaction as codec::Decode>::decode(
-                                        &mut input.as_slice(),
-                                    )?;
-                                eip1995_tx.max_priority_fee_per_gas = eip1995_tx
-                                    .max_priority_fee_per_gas
-                                    + sp_core::U256::from(*tip + 100u128);
-                                let evm_tx = self
-                                    .build_eip1559_tx_to_v2(eip1995_tx)
-                                    .map_err(|e| Error::Other(e))?;
-                                let evm_call = crate::deepsafe::tx().ethereum().transact(evm_tx);
-                                client.tx().create_unsigned(&evm_call)?
-                            } else {
-                                client.tx().create_signed_with_nonce(
-                                    inner_call,
-                                    signer,
-                                    key,
-                                    BaseExtrinsicParamsBuilder::new().tip(*tip + 100),
-                                )?
-                            };
+                        if let Some((inner_call, tip)) = call_cache.get_mut(&key) {
+                            let tx = client.tx().create_signed_with_nonce(
+                                inner_call,
+                                signer,
+                                key,
+                                BaseExtrinsicParamsBuilder::new().tip(*tip + 100),
+                            )?;
                             let tx_hash = tx.submit().await;
                             log::warn!(target: "subxt", "re-submit call with nonce: {}, tip: {:?}, res: {:?}", key, *tip + 100, tx_hash);
                             //update tip
@@ -280,14 +536,27 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
             &call,
             signer,
             target_nonce,
-            Default::default(),
+            self.extrinsic_params(&client, &options).await?,
         )?;
-        let tx_hash = match tx.submit().await {
+        let submitted = match &self.tx_tracker {
+            Some(tracker) => match tx.submit_and_watch().await {
+                Ok(progress) => {
+                    let hash = progress.extrinsic_hash();
+                    tracker
+                        .track(progress, Some(target_nonce), call_names(call.as_ref()))
+                        .await;
+                    Ok(hash)
+                }
+                Err(e) => Err(e),
+            },
+            None => tx.submit().await,
+        };
+        let tx_hash = match submitted {
             Ok(tx) => {
                 log::debug!(target: "subxt::nonce", "inner_nonce {}, insert cache for nonce: {}", target_nonce + 1, target_nonce);
                 *inner_nonce = target_nonce + 1;
                 // update call_cache
-                call_cache.insert(target_nonce, (call, false, vec![], 0));
+                call_cache.insert(target_nonce, (call, options.tip));
                 tx
             }
             Err(e) => return Err(e),
@@ -298,6 +567,25 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         Ok(tx_hash)
     }
 
+    /// Extrinsic params of 'options', a mortal era starts at the latest finalized block.
+    async fn extrinsic_params(
+        &self,
+        client: &OnlineClient<DeepSafeConfig>,
+        options: &SubmitOptions,
+    ) -> Result<PolkadotExtrinsicParamsBuilder<DeepSafeConfig>, Error> {
+        let params = BaseExtrinsicParamsBuilder::new().tip(options.tip);
+        match options.mortality {
+            Some(period) => {
+                let hash = client.rpc().finalized_head().await?;
+                let header = client.rpc().header(Some(hash)).await?.ok_or_else(|| {
+                    Error::Other(format!("header of finalized block {hash:?} not found"))
+                })?;
+                Ok(params.era(Era::mortal(period, header.number as u64), hash))
+            }
+            None => Ok(params),
+        }
+    }
+
     pub async fn signed_tx_encode_to_bytes<Call: TxPayload + 'static + Send + Sync>(
         &self,
         call: Call,
@@ -333,28 +621,13 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
                 if *inner_nonce - chain_nonce >= self.cache_size_for_call {
                     log::warn!(target: "subxt", "Some errors occurred to nonce inner {}, chain {}", *inner_nonce, chain_nonce);
                     for key in chain_nonce..*inner_nonce {
-                        if let Some((inner_call, by_evm, input, tip)) = call_cache.get_mut(&key) {
-                            let tx = if *by_evm {
-                                let mut eip1995_tx =
-                                    <ethereum::EIP1559Transaction as codec::Decode>::decode(
-                                        &mut input.as_slice(),
-                                    )?;
-                                eip1995_tx.max_priority_fee_per_gas = eip1995_tx
-                                    .max_priority_fee_per_gas
-                                    + sp_core::U256::from(*tip + 100u128);
-                                let evm_tx = self
-                                    .build_eip1559_tx_to_v2(eip1995_tx)
-                                    .map_err(|e| Error::Other(e))?;
-                                let evm_call = crate::deepsafe::tx().ethereum().transact(evm_tx);
-                                client.tx().create_unsigned(&evm_call)?
-                            } else {
-                                client.tx().create_signed_with_nonce(
-                                    inner_call,
-                                    signer,
-                                    key,
-                                    BaseExtrinsicParamsBuilder::new().tip(*tip + 100),
-                                )?
-                            };
+                        if let Some((inner_call, tip)) = call_cache.get_mut(&key) {
+                            let tx = client.tx().create_signed_with_nonce(
+                                inner_call,
+                                signer,
+                                key,
+                                BaseExtrinsicParamsBuilder::new().tip(*tip + 100),
+                            )?;
                             let tx_hash = tx.submit().await;
                             log::warn!(target: "subxt", "re-submit call with nonce: {}, tip: {:?}, res: {:?}", key, *tip + 100, tx_hash);
                             //update tip
@@ -386,14 +659,47 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         Ok(tx.into_encoded())
     }
 
+    /// Params and encoded metadata of the node to build 'OfflineSubClient' on an air-gapped machine,
+    /// the metadata and runtime version are fetched at the same best block, not from the cache.
+    pub async fn offline_client_params(&self) -> Result<(OfflineParams, Vec<u8>), Error> {
+        let client = self.client.read().await;
+        let at = client
+            .rpc()
+            .block_hash(None)
+            .await?
+            .ok_or_else(|| Error::Other("no best block hash".to_string()))?;
+        let metadata: sp_core::Bytes = client
+            .rpc()
+            .request("state_getMetadata", rpc_params![at])
+            .await?;
+        let runtime_version = client.rpc().runtime_version(Some(at)).await?;
+        let params = OfflineParams {
+            genesis_hash: client.genesis_hash(),
+            spec_version: runtime_version.spec_version,
+            transaction_version: runtime_version.transaction_version,
+        };
+        Ok((params, metadata.0))
+    }
+
     pub async fn submit_extrinsic_without_signer<Call: TxPayload + 'static + Send + Sync>(
         &self,
         call: Call,
     ) -> Result<Hash, Error> {
         let timer = Instant::now();
         let client = self.client.read().await;
-        let tx = client.tx().create_unsigned(&Box::new(call))?;
-        let tx_hash = tx.submit().await?;
+        let call = Box::new(call);
+        let tx = client.tx().create_unsigned(&call)?;
+        let tx_hash = match &self.tx_tracker {
+            Some(tracker) => {
+                let progress = tx.submit_and_watch().await?;
+                let hash = progress.extrinsic_hash();
+                tracker
+                    .track(progress, None, call_names(call.as_ref()))
+                    .await;
+                hash
+            }
+            None => tx.submit().await?,
+        };
         if timer.elapsed().as_millis() > self.warn_time {
             log::warn!(target: "subxt", "submit_extrinsic_without_signer exceed warn_time: {} millis", timer.elapsed().as_millis());
         }
@@ -434,14 +740,33 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
     ) -> Result<Hash, Error> {
         let timer = Instant::now();
         let client = self.client.read().await;
+        let tracked = self.tx_tracker.as_ref().map(|tracker| {
+            // the bytes may be signed offline, take the nonce and names from the bytes
+            let (nonce, names) =
+                match crate::extrinsic::decode_extrinsic(&call_bytes, &client.metadata()) {
+                    Ok(decoded) => (decoded.nonce, (decoded.pallet, decoded.call)),
+                    Err(_) => (None, Default::default()),
+                };
+            (tracker, nonce, names)
+        });
         let tx = SubmittableExtrinsic::from_bytes(client.clone(), call_bytes);
-        let tx_hash = tx.submit().await?;
+        let tx_hash = match tracked {
+            Some((tracker, nonce, names)) => {
+                let progress = tx.submit_and_watch().await?;
+                let hash = progress.extrinsic_hash();
+                tracker.track(progress, nonce, names).await;
+                hash
+            }
+            None => tx.submit().await?,
+        };
         if timer.elapsed().as_millis() > self.warn_time {
             log::warn!(target: "subxt", "submit_extrinsic_without_signer exceed warn_time: {} millis", timer.elapsed().as_millis());
         }
         Ok(tx_hash)
     }
 
+    /// The tx is recorded by 'tx_tracker' if set, but its status is only followed by the returned
+    /// progress, report it by 'TxTracker::set_status' if needed.
     pub async fn submit_extrinsic_without_signer_and_watch<Call: TxPayload>(
         &self,
         call: Call,
@@ -450,6 +775,11 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         let client = self.client.read().await;
         let tx = client.tx().create_unsigned(&call)?;
         let tx_process = tx.submit_and_watch().await;
+        if let (Some(tracker), Ok(progress)) = (&self.tx_tracker, &tx_process) {
+            tracker
+                .record(progress.extrinsic_hash(), None, call_names(&call))
+                .await;
+        }
         if timer.elapsed().as_millis() > self.warn_time {
             log::warn!(target: "subxt", "submit_extrinsic_without_signer_and_watch exceed warn_time: {} millis", timer.elapsed().as_millis());
         }
@@ -459,14 +789,26 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
     pub async fn query_storage<F: StorageAddress<IsFetchable = Yes>>(
         &self,
         store_query: F,
-        at_block: Option<Hash>,
+        at_block: At,
     ) -> Result<Option<F::Target>, Error> {
         let timer = Instant::now();
         self.check_client_runtime_version_and_update().await?;
-        let storage_client = self.client.read().await.storage();
-        let res = match at_block {
-            Some(block) => storage_client.at(block).fetch(&store_query).await,
-            None => storage_client.at_latest().await?.fetch(&store_query).await,
+        let res = if let Some(cache) = &self.query_cache {
+            let metadata = self.client.read().await.metadata();
+            self.fetch_storage_bytes_with_cache(cache, &store_query, at_block)
+                .await
+                .and_then(|value| {
+                    value
+                        .map(|bytes| decode_storage_value(&store_query, &bytes, &metadata))
+                        .transpose()
+                })
+        } else {
+            let at_block = self.resolve_at(at_block).await?;
+            let storage_client = self.client.read().await.storage();
+            match at_block {
+                Some(block) => storage_client.at(block).fetch(&store_query).await,
+                None => storage_client.at_latest().await?.fetch(&store_query).await,
+            }
         };
         if timer.elapsed().as_millis() > self.warn_time {
             log::warn!(target: "subxt", "query_storage exceed warn_time: {} millis", timer.elapsed().as_millis());
@@ -478,10 +820,11 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         &self,
         store_query: F,
         page_sise: u32,
-        at_block: Option<Hash>,
+        at_block: At,
     ) -> Result<Vec<(StorageKey, F::Target)>, Error> {
         let timer = Instant::now();
         self.check_client_runtime_version_and_update().await?;
+        let at_block = self.resolve_at(at_block).await?;
         let storage_client = self.client.read().await.storage();
         let mut iter = match at_block {
             Some(block) => {
@@ -513,24 +856,35 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
     >(
         &self,
         store_query: F,
-        at_block: Option<Hash>,
+        at_block: At,
     ) -> Result<F::Target, Error> {
         let timer = Instant::now();
         self.check_client_runtime_version_and_update().await?;
-        let storage_client = self.client.read().await.storage();
-        let res = match at_block {
-            Some(block) => {
-                storage_client
-                    .at(block)
-                    .fetch_or_default(&store_query)
-                    .await
-            }
-            None => {
-                storage_client
-                    .at_latest()
-                    .await?
-                    .fetch_or_default(&store_query)
-                    .await
+        let res = if let Some(cache) = &self.query_cache {
+            let metadata = self.client.read().await.metadata();
+            self.fetch_storage_bytes_with_cache(cache, &store_query, at_block)
+                .await
+                .and_then(|value| match value {
+                    Some(bytes) => decode_storage_value(&store_query, &bytes, &metadata),
+                    None => decode_storage_default(&store_query, &metadata),
+                })
+        } else {
+            let at_block = self.resolve_at(at_block).await?;
+            let storage_client = self.client.read().await.storage();
+            match at_block {
+                Some(block) => {
+                    storage_client
+                        .at(block)
+                        .fetch_or_default(&store_query)
+                        .await
+                }
+                None => {
+                    storage_client
+                        .at_latest()
+                        .await?
+                        .fetch_or_default(&store_query)
+                        .await
+                }
             }
         };
         if timer.elapsed().as_millis() > self.warn_time {
@@ -539,6 +893,268 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         res
     }
 
+    /// Query storage together with its read proof, the proof is verified against the
+    /// state root of the block header before the value is decoded and returned.
+    pub async fn query_storage_with_proof<F: StorageAddress<IsFetchable = Yes>>(
+        &self,
+        store_query: F,
+        at_block: At,
+    ) -> Result<(Option<F::Target>, StorageProofBundle), Error> {
+        let timer = Instant::now();
+        self.check_client_runtime_version_and_update().await?;
+        let block = self.resolve_block_hash(at_block).await?;
+        let client = self.client.read().await;
+        let header = client
+            .rpc()
+            .header(Some(block))
+            .await?
+            .ok_or_else(|| Error::Other(format!("no header for block {block:?}")))?;
+        let metadata = client.metadata();
+        let key = storage_key_bytes(&store_query, &metadata)?;
+        let read_proof = client
+            .rpc()
+            .read_proof(vec![key.as_slice()], Some(block))
+            .await?;
+        let proof = read_proof.proof.into_iter().map(|node| node.0).collect();
+        let bundle =
+            StorageProofBundle::new_at(block, &header, key, proof).map_err(Error::Other)?;
+        let value = bundle
+            .value
+            .as_ref()
+            .map(|bytes| decode_storage_value(&store_query, bytes, &metadata))
+            .transpose()?;
+        if timer.elapsed().as_millis() > self.warn_time {
+            log::warn!(target: "subxt", "query_storage_with_proof exceed warn_time: {} millis", timer.elapsed().as_millis());
+        }
+        Ok((value, bundle))
+    }
+
+    /// Fetch raw storage value through the query cache, the latest head is resolved to
+    /// a block hash first so that cached values of older heads are invalidated.
+    async fn fetch_storage_bytes_with_cache<F: StorageAddress>(
+        &self,
+        cache: &RwLock<QueryCache>,
+        store_query: &F,
+        at_block: At,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let pinned = at_block != At::Latest;
+        let resolved = self.resolve_at(at_block).await?;
+        let client = self.client.read().await;
+        let block = match resolved {
+            Some(block) => block,
+            None => client
+                .rpc()
+                .block_hash(None)
+                .await?
+                .ok_or_else(|| Error::Other("get empty latest block".to_string()))?,
+        };
+        let key = storage_key_bytes(store_query, &client.metadata())?;
+        {
+            let mut cache = cache.write().await;
+            if !pinned {
+                cache.update_latest(block);
+            }
+            if let Some(value) = cache.get(block, &key) {
+                return Ok(value);
+            }
+        }
+        let value = client
+            .rpc()
+            .storage(&key, Some(block))
+            .await?
+            .map(|data| data.0);
+        cache
+            .write()
+            .await
+            .insert(block, key, value.clone(), pinned);
+        Ok(value)
+    }
+
+    /// Enable the read-through query cache with at most 'capacity' entries, or disable it by 'None'.
+    pub fn set_query_cache(&mut self, capacity: Option<usize>) {
+        self.query_cache =
+            capacity.map(|capacity| Arc::new(RwLock::new(QueryCache::new(capacity))));
+    }
+
+    /// Hit/miss counters of the query cache, 'None' if the cache is disabled.
+    pub async fn query_cache_stats(&self) -> Option<QueryCacheStats> {
+        match &self.query_cache {
+            Some(cache) => Some(cache.read().await.stats()),
+            None => None,
+        }
+    }
+
+    /// Fetch all storage addresses of the batch at the same block by one 'state_queryStorageAt' request.
+    pub async fn query_storage_batch<B: StorageBatch>(
+        &self,
+        batch: B,
+        at_block: At,
+    ) -> Result<B::Target, Error> {
+        let timer = Instant::now();
+        self.check_client_runtime_version_and_update().await?;
+        let at_block = self.resolve_at(at_block).await?;
+        let client = self.client.read().await;
+        let metadata = client.metadata();
+        let keys = batch.storage_keys(&metadata)?;
+        let change_sets = client
+            .rpc()
+            .query_storage_at(keys.iter().map(|key| key.as_slice()), at_block)
+            .await?;
+        let mut fetched = HashMap::new();
+        for change_set in change_sets {
+            for (key, data) in change_set.changes {
+                fetched.insert(key.0, data.map(|data| data.0));
+            }
+        }
+        let values = keys
+            .iter()
+            .map(|key| fetched.get(key).cloned().flatten())
+            .collect();
+        let res = batch.decode_values(values, &metadata);
+        if timer.elapsed().as_millis() > self.warn_time {
+            log::warn!(target: "subxt", "query_storage_batch exceed warn_time: {} millis", timer.elapsed().as_millis());
+        }
+        res
+    }
+
+    /// Compare entries under the storage 'prefix' between two blocks, entries are compared
+    /// by raw bytes and decoded with the same decoder as the typed queries.
+    /// 'prefix' could be a root address like 'committees_root()' or a partial one.
+    pub async fn storage_diff<F: StorageAddress<IsIterable = Yes>>(
+        &self,
+        prefix: F,
+        from: At,
+        to: At,
+    ) -> Result<StorageDiff<F::Target>, Error> {
+        let mut diffs = self.storage_diffs(vec![prefix], from, to).await?;
+        Ok(diffs.remove(0))
+    }
+
+    /// Same as 'storage_diff' for a set of prefixes, all between the same two blocks even if
+    /// 'from' or 'to' is the latest block. 'prefixes' could be a `Vec` of the same address type,
+    /// or a tuple of different ones like '(committees_root(), devices_root())'.
+    pub async fn storage_diffs<B: StorageDiffBatch>(
+        &self,
+        prefixes: B,
+        from: At,
+        to: At,
+    ) -> Result<B::Target, Error> {
+        let timer = Instant::now();
+        self.check_client_runtime_version_and_update().await?;
+        let from = self.resolve_block_hash(from).await?;
+        let to = self.resolve_block_hash(to).await?;
+        let metadata = self.client.read().await.metadata();
+        let mut entries = Vec::new();
+        for prefix_bytes in prefixes.prefix_keys(&metadata)? {
+            let from_entries = self.fetch_prefix_entries(&prefix_bytes, from).await?;
+            let to_entries = self.fetch_prefix_entries(&prefix_bytes, to).await?;
+            entries.push((from_entries, to_entries));
+        }
+        let res = prefixes.decode_diffs(entries, (from, to), &metadata);
+        if timer.elapsed().as_millis() > self.warn_time {
+            log::warn!(target: "subxt", "storage_diffs exceed warn_time: {} millis", timer.elapsed().as_millis());
+        }
+        res
+    }
+
+    /// All raw entries under the storage prefix at the block, fetched page by page.
+    async fn fetch_prefix_entries(
+        &self,
+        prefix: &[u8],
+        block: Hash,
+    ) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, Error> {
+        let client = self.client.read().await;
+        let mut entries = BTreeMap::new();
+        let mut start_key: Option<Vec<u8>> = None;
+        loop {
+            let keys = client
+                .rpc()
+                .storage_keys_paged(prefix, STORAGE_PAGE_SIZE, start_key.as_deref(), Some(block))
+                .await?;
+            if keys.is_empty() {
+                break;
+            }
+            let change_sets = client
+                .rpc()
+                .query_storage_at(keys.iter().map(|key| key.as_slice()), Some(block))
+                .await?;
+            for change_set in change_sets {
+                for (key, data) in change_set.changes {
+                    if let Some(data) = data {
+                        entries.insert(key.0, data.0);
+                    }
+                }
+            }
+            if keys.len() < STORAGE_PAGE_SIZE as usize {
+                break;
+            }
+            start_key = keys.last().cloned();
+        }
+        Ok(entries)
+    }
+
+    /// Resolve 'At' to a concrete block hash, the latest block is fetched from the node.
+    pub async fn resolve_block_hash(&self, at: At) -> Result<Hash, Error> {
+        match self.resolve_at(at).await? {
+            Some(block) => Ok(block),
+            None => self
+                .client
+                .read()
+                .await
+                .rpc()
+                .block_hash(None)
+                .await?
+                .ok_or_else(|| Error::Other("no latest block hash".to_string())),
+        }
+    }
+
+    /// Resolve 'At' to a block hash, 'None' means the latest block.
+    pub async fn resolve_at(&self, at: At) -> Result<Option<Hash>, Error> {
+        match at {
+            At::Latest => Ok(None),
+            At::Hash(hash) => Ok(Some(hash)),
+            At::Finalized => self
+                .client
+                .read()
+                .await
+                .rpc()
+                .finalized_head()
+                .await
+                .map(Some),
+            At::Number(number) => {
+                let known_finalized = {
+                    let cache = self.block_hash_cache.read().await;
+                    if let Some(hash) = cache.hashes.get(&number) {
+                        return Ok(Some(*hash));
+                    }
+                    cache.finalized
+                };
+                let client = self.client.read().await;
+                // read the finalized head before the hash, so that a hash fetched before the
+                // finality moves is never cached as finalized
+                let mut finalized = known_finalized;
+                if number > finalized {
+                    let finalized_hash = client.rpc().finalized_head().await?;
+                    if let Some(header) = client.rpc().header(Some(finalized_hash)).await? {
+                        finalized = header.number;
+                    }
+                }
+                let hash = client
+                    .rpc()
+                    .block_hash(Some(number.into()))
+                    .await?
+                    .ok_or_else(|| Error::Other(format!("no block hash for block {number}")))?;
+                drop(client);
+                let mut cache = self.block_hash_cache.write().await;
+                cache.finalized = std::cmp::max(cache.finalized, finalized);
+                if number <= finalized {
+                    cache.insert(number, hash);
+                }
+                Ok(Some(hash))
+            }
+        }
+    }
+
     pub async fn query_constant<Address: subxt::constants::ConstantAddress>(
         &self,
         address: Address,
@@ -587,46 +1203,579 @@ impl SubClient<DeepSafeConfig, DeepSafeSigner<DeepSafeConfig>> {
         res
     }
 
+    /// Track every tx submitted by the client with 'tracker', or stop tracking by 'None'.
+    pub fn set_tx_tracker(&mut self, tracker: Option<TxTracker>) {
+        self.tx_tracker = tracker;
+    }
+
+    /// Enable or disable the fee guard, signed tx is refused before submitting when the
+    /// transferable balance of the signer is lower than the estimated fee.
+    pub fn set_fee_guard(&mut self, enabled: bool) {
+        self.fee_guard = enabled;
+    }
+
+    /// Estimate fees and gas of the evm tx built by the relay helpers with 'estimator',
+    /// or use the fixed defaults of 'precompile' by 'None'.
+    pub fn set_evm_fee_estimator(&mut self, estimator: Option<EvmFeeEstimator>) {
+        self.evm_fee_estimator = estimator;
+    }
+
+    /// Call runtime api 'method' with the SCALE encoded 'params' at 'at_block' and decode the result.
+    pub async fn runtime_api_call<R: Decode>(
+        &self,
+        method: &str,
+        params: &[u8],
+        at_block: At,
+    ) -> Result<R, Error> {
+        let timer = Instant::now();
+        self.check_client_runtime_version_and_update().await?;
+        let at = self.resolve_at(at_block).await?;
+        let bytes = self
+            .client
+            .read()
+            .await
+            .rpc()
+            .state_call(method, Some(params), at)
+            .await?;
+        let res = R::decode(&mut &bytes.0[..])?;
+        if timer.elapsed().as_millis() > self.warn_time {
+            log::warn!(target: "subxt", "runtime_api_call {} exceed warn_time: {} millis", method, timer.elapsed().as_millis());
+        }
+        Ok(res)
+    }
+
+    /// Estimate the fee of 'call' signed by the signer, by runtime api 'TransactionPaymentApi_query_info'.
+    pub async fn estimate_fee<Call: TxPayload>(
+        &self,
+        call: &Call,
+    ) -> Result<RuntimeDispatchInfo, Error> {
+        let timer = Instant::now();
+        self.check_client_runtime_version_and_update().await?;
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| Error::Other("empty sk to sign and estimate tx fee".to_string()))?;
+        let client = self.client.read().await;
+        let nonce = client.tx().account_nonce(signer.account_id()).await? as u32;
+        let tx = client
+            .tx()
+            .create_signed_with_nonce(call, signer, nonce, Default::default())?;
+        let mut params = tx.encoded().to_vec();
+        (tx.encoded().len() as u32).encode_to(&mut params);
+        let bytes = client
+            .rpc()
+            .state_call("TransactionPaymentApi_query_info", Some(&params), None)
+            .await?;
+        let res = RuntimeDispatchInfo::decode(&mut &bytes.0[..])?;
+        if timer.elapsed().as_millis() > self.warn_time {
+            log::warn!(target: "subxt", "estimate_fee exceed warn_time: {} millis", timer.elapsed().as_millis());
+        }
+        Ok(res)
+    }
+
+    /// Return the estimated fee of 'call', or an error if the transferable balance of the signer
+    /// can't cover it, the existential deposit is kept as the fee is withdrawn in keep-alive mode.
+    pub async fn ensure_fee_affordable<Call: TxPayload>(&self, call: &Call) -> Result<u128, Error> {
+        let fee = self.estimate_fee(call).await?.partial_fee;
+        let account = self.account_id().await;
+        let balance = crate::query::balances::transferable_balance(
+            self,
+            crate::deepsafe::runtime_types::fp_account::AccountId20(account.0),
+            crate::query::balances::Preservation::Preserve,
+            At::Latest,
+        )
+        .await?;
+        if balance < fee {
+            return Err(Error::Other(format!(
+                "insufficient balance of signer 0x{}: transferable {}, estimated fee {}",
+                hex::encode(account.0),
+                balance,
+                fee
+            )));
+        }
+        Ok(fee)
+    }
+
+    /// Dry run 'call' signed by the signer with the era, tip and nonce of 'options' at the best block,
+    /// neither the tx pool nor the nonce of the client is touched.
+    pub async fn dry_run<Call: TxPayload>(
+        &self,
+        call: &Call,
+        options: SubmitOptions,
+    ) -> Result<DryRunOutcome, Error> {
+        let timer = Instant::now();
+        self.check_client_runtime_version_and_update().await?;
+        let at = self.resolve_block_hash(At::Latest).await?;
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| Error::Other("empty sk to sign and dry run tx".to_string()))?;
+        let client = self.client.read().await;
+        let nonce = match options.nonce {
+            Some(nonce) => nonce,
+            None => client.tx().account_nonce(signer.account_id()).await? as u32,
+        };
+        let tx = client.tx().create_signed_with_nonce(
+            call,
+            signer,
+            nonce,
+            self.extrinsic_params(&client, &options).await?,
+        )?;
+        let res = match validate_transaction(&client, tx.encoded(), at).await? {
+            TxValidity::Invalid(invalid) => DryRunOutcome::Invalid(invalid),
+            TxValidity::Valid(valid) => {
+                let bytes: sp_core::Bytes = client
+                    .rpc()
+                    .request(
+                        "system_dryRun",
+                        rpc_params![format!("0x{}", hex::encode(tx.encoded())), at],
+                    )
+                    .await?;
+                match ApplyExtrinsicResult::decode(&mut &bytes.0[..])? {
+                    Ok(Ok(())) => DryRunOutcome::Success(valid),
+                    Ok(Err(e)) => DryRunOutcome::DispatchFailed(
+                        valid,
+                        dispatch_error_name(&e, &client.metadata()),
+                    ),
+                    Err(e) => DryRunOutcome::Invalid(e.into()),
+                }
+            }
+        };
+        if timer.elapsed().as_millis() > self.warn_time {
+            log::warn!(target: "subxt", "dry_run exceed warn_time: {} millis", timer.elapsed().as_millis());
+        }
+        Ok(res)
+    }
+
+    /// Validate unsigned 'call' by the runtime at the best block without submitting it to the tx pool.
+    pub async fn validate_unsigned<Call: TxPayload>(
+        &self,
+        call: &Call,
+    ) -> Result<TxValidity, Error> {
+        let timer = Instant::now();
+        self.check_client_runtime_version_and_update().await?;
+        let at = self.resolve_block_hash(At::Latest).await?;
+        let client = self.client.read().await;
+        let tx = client.tx().create_unsigned(call)?;
+        let res = validate_transaction(&client, tx.encoded(), at).await;
+        if timer.elapsed().as_millis() > self.warn_time {
+            log::warn!(target: "subxt", "validate_unsigned exceed warn_time: {} millis", timer.elapsed().as_millis());
+        }
+        res
+    }
+
+    /// Read-only evm call of 'to' with 'input' at 'at_block' by runtime api 'EthereumRuntimeRPCApi_call',
+    /// sent by the signer or the zero address. Return the output, or an error with the revert reason.
+    pub async fn evm_call(&self, to: H160, input: Vec<u8>, at_block: At) -> Result<Vec<u8>, Error> {
+        let request = EvmCallRequest {
+            from: self
+                .signer
+                .as_ref()
+                .map(|signer| H160(signer.account_id().0))
+                .unwrap_or_default(),
+            to: Some(to),
+            data: input,
+            ..Default::default()
+        };
+        let info =
+            match crate::query::ethereum::evm_call_raw(self, &request, false, at_block).await? {
+                Ok(info) => info,
+                Err(e) => {
+                    let metadata = self.client.read().await.metadata();
+                    return Err(Error::Other(format!(
+                        "evm call of {to:?} failed: {}",
+                        dispatch_error_name(&e, &metadata)
+                    )));
+                }
+            };
+        if info.is_succeed() {
+            return Ok(info.value);
+        }
+        let reason =
+            revert_reason(&info.value).unwrap_or_else(|| format!("{:?}", info.exit_reason));
+        Err(Error::Other(format!(
+            "evm call of {to:?} reverted: {reason}"
+        )))
+    }
+
+    /// Same as 'evm_call' and decode the output as 'T'.
+    pub async fn evm_call_decode<T: Codec>(
+        &self,
+        to: H160,
+        input: Vec<u8>,
+        at_block: At,
+    ) -> Result<T, Error> {
+        let output = self.evm_call(to, input, at_block).await?;
+        decode_output(&output).map_err(Error::Other)
+    }
+
+    /// Decode extrinsic bytes with the metadata of the node, i.e. to audit call bytes before relaying.
+    pub async fn decode_extrinsic(&self, bytes: &[u8]) -> Result<DecodedExtrinsic, String> {
+        self.check_client_runtime_version_and_update()
+            .await
+            .map_err(|e| e.to_string())?;
+        let metadata = self.client.read().await.metadata();
+        decode_extrinsic(bytes, &metadata)
+    }
+
+    /// Subscribe changes of the storage 'addresses', the receiver gets
+    /// '(block_hash, index of address, decoded value)' whenever a watched value changes,
+    /// 'None' means the storage is removed. Current values are sent first.
+    ///
+    /// The subscription is made again after 'rebuild_client', values unchanged
+    /// since the last notification are not sent twice. It stops when the receiver is dropped.
+    pub fn subscribe_storage<F>(
+        &self,
+        addresses: Vec<F>,
+        buffer: usize,
+    ) -> Receiver<(Hash, usize, Option<F::Target>)>
+    where
+        F: StorageAddress<IsFetchable = Yes> + Send + Sync + 'static,
+        F::Target: Send,
+    {
+        let (sender, receiver) = tokio::sync::mpsc::channel(buffer);
+        let sub_client = self.clone();
+        tokio::spawn(async move {
+            // storage key -> last value sent
+            let mut last_values: HashMap<Vec<u8>, Option<Vec<u8>>> = HashMap::new();
+            loop {
+                let generation = sub_client.client_generation.load(AtomicOrdering::SeqCst);
+                let client = sub_client.client.read().await.clone();
+                let metadata = client.metadata();
+                let keys = match addresses
+                    .iter()
+                    .map(|address| storage_key_bytes(address, &metadata))
+                    .collect::<Result<Vec<_>, Error>>()
+                {
+                    Ok(keys) => keys,
+                    Err(e) => {
+                        log::error!(target: "subxt", "build storage keys for subscription failed for: {:?}", e);
+                        return;
+                    }
+                };
+                let params = keys
+                    .iter()
+                    .map(|key| format!("0x{}", hex::encode(key)))
+                    .collect::<Vec<_>>();
+                let subscription = client
+                    .rpc()
+                    .subscribe::<StorageChangeSet<Hash>>(
+                        "state_subscribeStorage",
+                        rpc_params![params],
+                        "state_unsubscribeStorage",
+                    )
+                    .await;
+                match subscription {
+                    Ok(mut subscription) => loop {
+                        // the receiver is dropped, return to drop and unsubscribe the subscription
+                        if sender.is_closed() {
+                            return;
+                        }
+                        if sub_client.client_generation.load(AtomicOrdering::SeqCst) != generation {
+                            log::info!(target: "subxt", "client rebuilt, resubscribe storage...");
+                            break;
+                        }
+                        let change_set = match tokio::time::timeout(
+                            std::time::Duration::from_secs(1),
+                            subscription.next(),
+                        )
+                        .await
+                        {
+                            Ok(Some(Ok(change_set))) => change_set,
+                            // check the receiver and the client generation again
+                            Err(_) => continue,
+                            Ok(Some(Err(e))) => {
+                                log::warn!(target: "subxt", "storage subscription failed for: {:?}", e);
+                                break;
+                            }
+                            Ok(None) => {
+                                log::warn!(target: "subxt", "storage subscription dropped");
+                                break;
+                            }
+                        };
+                        for (key, data) in change_set.changes {
+                            let value = data.map(|data| data.0);
+                            if last_values.get(&key.0) == Some(&value) {
+                                continue;
+                            }
+                            let index = match keys.iter().position(|k| *k == key.0) {
+                                Some(index) => index,
+                                None => continue,
+                            };
+                            let decoded = match value
+                                .as_ref()
+                                .map(|bytes| {
+                                    decode_storage_value(&addresses[index], bytes, &metadata)
+                                })
+                                .transpose()
+                            {
+                                Ok(decoded) => decoded,
+                                Err(e) => {
+                                    log::error!(target: "subxt", "decode subscribed storage failed for: {:?}", e);
+                                    continue;
+                                }
+                            };
+                            last_values.insert(key.0, value);
+                            if sender
+                                .send((change_set.block, index, decoded))
+                                .await
+                                .is_err()
+                            {
+                                return;
+                            }
+                        }
+                    },
+                    Err(e) => {
+                        log::warn!(target: "subxt", "subscribe storage failed for: {:?}", e);
+                        if let Err(e) = sub_client.handle_error(e).await {
+                            log::error!(target: "subxt", "handle subscribe storage error: {:?}", e);
+                        }
+                    }
+                }
+                if sender.is_closed() {
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            }
+        });
+        receiver
+    }
+
     pub fn build_eip1559_tx_to_v2(
         &self,
         tx: ethereum::EIP1559Transaction,
     ) -> Result<EvmTransaction, String> {
-        let tx = ethereum::EIP1559TransactionMessage::from(tx);
+        self.sign_evm_tx(&ethereum::EIP1559TransactionMessage::from(tx).into())
+    }
+
+    /// Sign the evm tx by the signer of the client and convert it to the runtime type.
+    pub fn sign_evm_tx(&self, builder: &EvmTxBuilder) -> Result<EvmTransaction, String> {
         let sk = self
             .signer
             .clone()
             .ok_or("Not set deepsafe client signer")?
             .signer()
             .serialize();
-        let secret = secp256k1::SecretKey::parse(&sk)
-            .map_err(|e| format!("Parse deepsafe signer sk failed for: {:?}", e))?;
-        let signing_message =
-            secp256k1::Message::parse_slice(&tx.hash()[..]).map_err(|e| e.to_string())?;
-        let (signature, recid) = secp256k1::sign(&signing_message, &secret);
-        let rs = signature.serialize();
-        let r = Hash::from_slice(&rs[0..32]);
-        let s = Hash::from_slice(&rs[32..64]);
-        Ok(EvmTransaction::EIP1559(EIP1559Transaction {
-            chain_id: tx.chain_id,
-            nonce: crate::deepsafe::runtime_types::primitive_types::U256(tx.nonce.0),
-            max_priority_fee_per_gas: crate::deepsafe::runtime_types::primitive_types::U256(
-                tx.max_priority_fee_per_gas.0,
-            ),
-            max_fee_per_gas: crate::deepsafe::runtime_types::primitive_types::U256(
-                tx.max_fee_per_gas.0,
-            ),
-            gas_limit: crate::deepsafe::runtime_types::primitive_types::U256(tx.gas_limit.0),
-            action: match tx.action {
-                ethereum::TransactionAction::Call(addr) => TransactionAction::Call(addr),
-                _ => return Err(format!("Invalid evm tx action: {:?}", tx.action)),
-            },
-            value: crate::deepsafe::runtime_types::primitive_types::U256(tx.value.0),
-            input: tx.input,
-            access_list: vec![],
-            odd_y_parity: recid.serialize() != 0,
-            r,
-            s,
-        }))
+        to_runtime_transaction(&builder.sign(&sk)?)
+    }
+
+    /// Sign and submit the evm tx by the signer with the nonce of 'evm_nonce_tracker', the stuck
+    /// pending evm tx are replaced with higher fees first. Accounts of DeepSafe share one nonce
+    /// between substrate and evm tx, so 'inner_nonce' is kept ahead of the reserved evm nonce.
+    pub async fn submit_evm_tx(&self, mut builder: EvmTxBuilder) -> Result<Hash, String> {
+        let timer = Instant::now();
+        let address = H160(
+            self.signer
+                .as_ref()
+                .ok_or("empty sk to sign and submit evm tx")?
+                .account_id()
+                .0,
+        );
+        let mut inner_nonce = self.inner_nonce.write().await;
+        let chain_nonce = crate::query::ethereum::account_basic(self, address, At::Latest)
+            .await
+            .map_err(|e| e.to_string())?
+            .nonce;
+        let stuck = self
+            .evm_nonce_tracker
+            .stuck(address, chain_nonce, SystemTime::now())
+            .await;
+        if !stuck.is_empty() {
+            log::warn!(target: "subxt", "{} evm tx stuck from nonce {}", stuck.len(), chain_nonce);
+            for tx in stuck {
+                let res = self
+                    .submit_evm_tx_with_nonce(
+                        address,
+                        replacement(&tx, REPLACEMENT_BUMP_PERCENT),
+                        tx.replaced + 1,
+                    )
+                    .await;
+                log::warn!(target: "subxt", "re-submit evm tx with nonce: {}, res: {:?}", tx.nonce, res);
+            }
+        }
+        let nonce = self
+            .evm_nonce_tracker
+            .reserve(
+                address,
+                std::cmp::max(chain_nonce, sp_core::U256::from(*inner_nonce)),
+            )
+            .await;
+        builder.nonce = nonce;
+        let res = match self.submit_evm_tx_with_nonce(address, builder, 0).await {
+            Ok(hash) => {
+                *inner_nonce = std::cmp::max(*inner_nonce, nonce.low_u32() + 1);
+                Ok(hash)
+            }
+            Err(e) => {
+                self.evm_nonce_tracker.release(address, nonce).await;
+                Err(e)
+            }
+        };
+        if timer.elapsed().as_millis() > self.warn_time {
+            log::warn!(target: "subxt", "submit_evm_tx exceed warn_time: {} millis", timer.elapsed().as_millis());
+        }
+        res
+    }
+
+    async fn submit_evm_tx_with_nonce(
+        &self,
+        address: H160,
+        builder: EvmTxBuilder,
+        replaced: u32,
+    ) -> Result<Hash, String> {
+        let sk = self
+            .signer
+            .clone()
+            .ok_or("Not set deepsafe client signer")?
+            .signer()
+            .serialize();
+        let signed = builder.sign(&sk)?;
+        let hash =
+            crate::submit::ethereum::transact(self, to_runtime_transaction(&signed)?).await?;
+        self.evm_nonce_tracker
+            .record(
+                address,
+                PendingEvmTx {
+                    nonce: builder.nonce,
+                    hash: signed.hash(),
+                    builder,
+                    submitted_at: SystemTime::now(),
+                    replaced,
+                },
+            )
+            .await;
+        Ok(hash)
+    }
+}
+
+impl<C, P> SubClient<C, P>
+where
+    C: Config + Send + Sync,
+    P: Signer<C> + Clone + Send + Sync + 'static,
+{
+    /// Subscribe runtime version updates in the background, the client is only rebuilt when
+    /// a real runtime upgrade arrives, and the per-call runtime version rpc is skipped
+    /// while the subscription is alive. Started by the DeepSafe constructors, at most one
+    /// subscription runs per client, it stops by the returned handle or once all clones of
+    /// the client are dropped.
+    pub fn watch_runtime_upgrade(&self) -> RuntimeWatchHandle {
+        let handle = self.runtime_watch_handle();
+        let mut task = self
+            .runtime_watcher
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if task.as_ref().map_or(false, |task| !task.is_finished()) {
+            return handle;
+        }
+        let sub_client = self.clone();
+        *task = Some(tokio::spawn(async move {
+            loop {
+                let subscription = sub_client
+                    .client
+                    .read()
+                    .await
+                    .rpc()
+                    .subscribe_runtime_version()
+                    .await;
+                match subscription {
+                    Ok(mut subscription) => {
+                        sub_client
+                            .runtime_watched
+                            .store(true, AtomicOrdering::SeqCst);
+                        loop {
+                            let runtime_version = match tokio::time::timeout(
+                                std::time::Duration::from_secs(1),
+                                subscription.next(),
+                            )
+                            .await
+                            {
+                                Ok(Some(runtime_version)) => runtime_version,
+                                Ok(None) => break,
+                                // check whether the client is still used
+                                Err(_) => {
+                                    if sub_client.only_used_by_background_task() {
+                                        sub_client
+                                            .runtime_watched
+                                            .store(false, AtomicOrdering::SeqCst);
+                                        return;
+                                    }
+                                    continue;
+                                }
+                            };
+                            match runtime_version {
+                                Ok(runtime_version) => {
+                                    sub_client.handle_runtime_version(runtime_version).await
+                                }
+                                Err(e) => {
+                                    log::warn!(target: "subxt", "runtime version subscription failed for: {:?}", e);
+                                    break;
+                                }
+                            }
+                        }
+                        sub_client
+                            .runtime_watched
+                            .store(false, AtomicOrdering::SeqCst);
+                        log::warn!(target: "subxt", "runtime version subscription dropped, try to rebuild client...");
+                        if let Err(e) = sub_client.rebuild_client().await {
+                            log::error!(target: "subxt", "rebuild client failed for: {:?}", e);
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!(target: "subxt", "subscribe runtime version failed for: {:?}", e);
+                        if let Err(e) = sub_client.handle_error(e).await {
+                            log::error!(target: "subxt", "handle subscribe runtime version error: {:?}", e);
+                        }
+                    }
+                }
+                if sub_client.only_used_by_background_task() {
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            }
+        }));
+        handle
+    }
+
+    /// Handle of the runtime version subscription, i.e. to stop the one started by the constructors.
+    pub fn runtime_watch_handle(&self) -> RuntimeWatchHandle {
+        RuntimeWatchHandle {
+            task: self.runtime_watcher.clone(),
+            watched: self.runtime_watched.clone(),
+        }
+    }
+
+    // the clone held by the subscription task is the last one, all handles of the user are dropped
+    fn only_used_by_background_task(&self) -> bool {
+        Arc::strong_count(&self.client) <= 1
+    }
+
+    /// Register a hook called after the client is rebuilt for a runtime upgrade,
+    /// i.e. to reload cached constants.
+    pub async fn on_runtime_upgrade<F: Fn(&RuntimeVersion) + Send + Sync + 'static>(
+        &self,
+        hook: F,
+    ) {
+        self.runtime_upgrade_hooks
+            .write()
+            .await
+            .push(Box::new(hook));
+    }
+
+    async fn handle_runtime_version(&self, runtime_version: RuntimeVersion) {
+        if runtime_version == self.client.read().await.runtime_version() {
+            return;
+        }
+        log::info!(target: "subxt", "runtime upgrade to spec_version {}, try to rebuild client...", runtime_version.spec_version);
+        match self.rebuild_client().await {
+            Ok(()) => {
+                for hook in self.runtime_upgrade_hooks.read().await.iter() {
+                    hook(&runtime_version);
+                }
+            }
+            Err(e) => {
+                log::error!(target: "subxt", "rebuild client for runtime upgrade failed for: {:?}", e)
+            }
+        }
     }
 }
 
@@ -651,7 +1800,7 @@ impl<C: Config, P: Signer<C> + Clone> SubClient<C, P> {
             fixed_ws_url = tmp.concat();
         }
         let subxt_client = OnlineClient::<C>::from_url(fixed_ws_url.clone()).await?;
-        Ok(SubClient {
+        let sub_client = SubClient {
             ws_url: fixed_ws_url,
             signer,
             client: Arc::new(RwLock::new(subxt_client)),
@@ -659,10 +1808,25 @@ impl<C: Config, P: Signer<C> + Clone> SubClient<C, P> {
             cache_size_for_call: cache_size_for_call.unwrap_or(10),
             call_cache: Arc::new(RwLock::new(HashMap::new())),
             warn_time: warn_time.unwrap_or(10000),
-        })
+            block_hash_cache: Arc::new(RwLock::new(BlockHashCache::default())),
+            query_cache: None,
+            runtime_watched: Arc::new(AtomicBool::new(false)),
+            runtime_watcher: Arc::new(std::sync::Mutex::new(None)),
+            runtime_upgrade_hooks: Arc::new(RwLock::new(Vec::new())),
+            client_generation: Arc::new(AtomicU64::new(0)),
+            fee_guard: false,
+            tx_tracker: None,
+            evm_fee_estimator: None,
+            evm_nonce_tracker: EvmNonceTracker::new(),
+        };
+        Ok(sub_client)
     }
 
     pub async fn check_client_runtime_version_and_update(&self) -> Result<(), Error> {
+        // runtime upgrades are handled by the background subscription, see 'watch_runtime_upgrade'
+        if self.runtime_watched.load(AtomicOrdering::SeqCst) {
+            return Ok(());
+        }
         let timer = Instant::now();
         let client = self.client.read().await;
         let res = match client.rpc().runtime_version(None).await {
@@ -687,11 +1851,19 @@ impl<C: Config, P: Signer<C> + Clone> SubClient<C, P> {
         res
     }
 
+    /// Compare the live node metadata with the compiled 'metadata.scale'.
+    pub async fn metadata_compatibility(&self) -> Result<CompatibilityReport, Error> {
+        let compiled = Metadata::decode(&mut &COMPILED_METADATA[..])?;
+        let live = self.client.read().await.metadata();
+        Ok(compare_metadata(&compiled, &live))
+    }
+
     pub async fn rebuild_client(&self) -> Result<(), Error> {
         let timer = Instant::now();
         let res = match OnlineClient::<C>::from_url(&self.ws_url).await {
             Ok(client) => {
                 *self.client.write().await = client;
+                self.client_generation.fetch_add(1, AtomicOrdering::SeqCst);
                 log::info!(target: "subxt", "rebuild client successful");
                 Ok(())
             }
@@ -727,6 +1899,40 @@ impl<C: Config, P: Signer<C> + Clone> SubClient<C, P> {
     }
 }
 
+/// Validate the encoded tx at block 'at' by runtime api 'TaggedTransactionQueue_validate_transaction'.
+async fn validate_transaction(
+    client: &OnlineClient<DeepSafeConfig>,
+    tx: &[u8],
+    at: Hash,
+) -> Result<TxValidity, Error> {
+    let mut params = TransactionSource::External.encode();
+    params.extend_from_slice(tx);
+    at.encode_to(&mut params);
+    let bytes = client
+        .rpc()
+        .state_call(
+            "TaggedTransactionQueue_validate_transaction",
+            Some(&params),
+            Some(at),
+        )
+        .await?;
+    Ok(TransactionValidity::decode(&mut &bytes.0[..])?.into())
+}
+
+/// 'Pallet.Error' of a module error, or the debug string for other dispatch errors.
+pub(crate) fn dispatch_error_name(err: &DispatchError, metadata: &Metadata) -> String {
+    if let DispatchError::Module(module) = err {
+        if let Some(name) = metadata.pallet_by_index(module.index).and_then(|pallet| {
+            pallet
+                .error_variant_by_index(module.error[0])
+                .map(|error| format!("{}.{}", pallet.name(), error.name))
+        }) {
+            return name;
+        }
+    }
+    format!("{err:?}")
+}
+
 pub fn default_port(scheme: &str) -> Option<u16> {
     match scheme {
         "http" | "ws" => Some(80),
@@ -745,7 +1951,7 @@ async fn test_rebuild_client() {
         .unwrap();
     loop {
         println!("try to query challenges");
-        let res = crate::query::mining::challenges(&client, 1, None)
+        let res = crate::query::mining::challenges(&client, 1, At::Latest)
             .await
             .unwrap();
         println!("query challenges result: {:?}", res);
@@ -759,7 +1965,7 @@ async fn test_query_iter() {
     let client = crate::client::SubClient::new_from_signer(&url, None, None, None)
         .await
         .unwrap();
-    let res = crate::query::committee::committees_iter(&client, 300, None)
+    let res = crate::query::committee::committees_iter(&client, 300, At::Latest)
         .await
         .unwrap();
     println!("res: {res:?}");
@@ -773,13 +1979,139 @@ async fn test_query_cmt() {
         .unwrap();
 
     for i in 1u32..426 {
-        let res = crate::query::committee::committees(&client, i, None)
+        let res = crate::query::committee::committees(&client, i, At::Latest)
             .await
             .unwrap();
         println!("res: {res:?}");
     }
 }
 
+#[tokio::test]
+async fn test_query_storage_batch() {
+    let url = "ws://127.0.0.1:9944".to_string();
+    let client = crate::client::SubClient::new_from_signer(&url, None, None, None)
+        .await
+        .unwrap();
+
+    let batch = (
+        crate::deepsafe::storage().committee().committees(1),
+        crate::deepsafe::storage()
+            .committee()
+            .committee_members(1, (1, 0)),
+        crate::deepsafe::storage().committee().candidate_links(1, 0),
+        crate::deepsafe::storage()
+            .committee()
+            .epoch_changes_failures(1, 0),
+    );
+    let (committee, members, links, failures) = client
+        .query_storage_batch(batch, At::Finalized)
+        .await
+        .unwrap();
+    assert_eq!(
+        committee,
+        crate::query::committee::committees(&client, 1, At::Finalized)
+            .await
+            .unwrap()
+    );
+    println!("members: {members:?}, links: {links:?}, failures: {failures:?}");
+
+    let res = crate::query::committee::committees_batch(&client, (1u32..10).collect(), At::Latest)
+        .await
+        .unwrap();
+    println!("res: {res:?}");
+}
+
+#[tokio::test]
+async fn test_query_storage_with_proof() {
+    let url = "ws://127.0.0.1:9944".to_string();
+    let client = crate::client::SubClient::new_from_signer(&url, None, None, None)
+        .await
+        .unwrap();
+
+    let store = crate::deepsafe::storage().committee().committees(1);
+    let (committee, mut bundle) = client
+        .query_storage_with_proof(store, At::Finalized)
+        .await
+        .unwrap();
+    assert_eq!(
+        committee,
+        crate::query::committee::committees(&client, 1, At::Hash(bundle.block_hash))
+            .await
+            .unwrap()
+    );
+    bundle.verify().unwrap();
+
+    // tampered value should be rejected
+    bundle.value = Some(vec![0u8; 4]);
+    assert!(bundle.verify().is_err());
+}
+
+#[tokio::test]
+async fn test_storage_diff() {
+    let url = "ws://127.0.0.1:9944".to_string();
+    let client = crate::client::SubClient::new_from_signer(&url, None, None, None)
+        .await
+        .unwrap();
+
+    let finalized = client.resolve_block_hash(At::Finalized).await.unwrap();
+    let diff =
+        crate::query::committee::committees_diff(&client, finalized.into(), finalized.into())
+            .await
+            .unwrap();
+    assert!(diff.is_empty());
+
+    let roots = (
+        crate::deepsafe::storage().committee().committees_root(),
+        crate::deepsafe::storage()
+            .committee()
+            .committee_members_root(),
+        crate::deepsafe::storage().mining().devices_root(),
+        crate::deepsafe::storage()
+            .channel()
+            .committee_fee_data_root(),
+    );
+    let (committees, members, devices, fee_data) = client
+        .storage_diffs(roots, finalized.into(), finalized.into())
+        .await
+        .unwrap();
+    assert!(committees.is_empty() && members.is_empty());
+    assert!(devices.is_empty() && fee_data.is_empty());
+
+    let diff = crate::query::committee::committees_diff(&client, At::Number(1), At::Finalized)
+        .await
+        .unwrap();
+    println!(
+        "added: {}, removed: {}, modified: {}",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.modified.len()
+    );
+}
+
+#[tokio::test]
+async fn test_subscribe_storage() {
+    let url = "ws://127.0.0.1:9944".to_string();
+    let client = crate::client::SubClient::new_from_signer(&url, None, None, None)
+        .await
+        .unwrap();
+
+    let mut receiver = crate::query::committee::subscribe_global_epoch(&client, 10);
+    // the current value is sent first
+    let (block, index, epoch) = receiver.recv().await.unwrap();
+    assert_eq!(index, 0);
+    assert_eq!(
+        epoch.unwrap_or_default(),
+        crate::query::committee::global_epoch(&client, At::Hash(block))
+            .await
+            .unwrap()
+    );
+
+    // keep receiving after the client is rebuilt
+    client.rebuild_client().await.unwrap();
+    let res = tokio::time::timeout(std::time::Duration::from_secs(3), receiver.recv()).await;
+    println!("after rebuild: {res:?}");
+}
+
 #[tokio::test]
 async fn test_nonce_roll_back() {
     std::env::set_var("RUST_LOG", "debug");
@@ -837,3 +2169,46 @@ async fn test_submit_tx_by_call_bytes() {
         .map_err(|e| e.to_string());
     log::info!("submit res: {res:?}");
 }
+
+#[tokio::test]
+async fn test_estimate_fee() {
+    use crate::DeepSafeSubClient;
+    use std::str::FromStr;
+
+    let url = "ws://127.0.0.1:9944".to_string();
+    let sk_bytes =
+        hex::decode("5fb92d6e98884f76de468fa3f6278f8807c48bebc13595d45af5bdc4da702133").unwrap(); // alice
+    let sk = SecretKey::parse_slice(&sk_bytes).unwrap();
+    let signer = DeepSafeSigner::new(sk);
+    let mut client = DeepSafeSubClient::new_from_signer(&url, Some(signer), None, Some(20))
+        .await
+        .unwrap();
+    let account = AccountId20::from_str("0x89Bdaf4AC10bC9d497BCa9a5cc37972026146E0E").unwrap();
+    let dst = crate::deepsafe::runtime_types::fp_account::AccountId20(account.0);
+    let call = crate::deepsafe::tx()
+        .balances()
+        .transfer_keep_alive(dst.into(), 100000);
+    let info = client.estimate_fee(&call).await.unwrap();
+    assert!(info.partial_fee > 0);
+    client.set_fee_guard(true);
+    assert_eq!(
+        client.ensure_fee_affordable(&call).await.unwrap(),
+        info.partial_fee
+    );
+}
+
+#[tokio::test]
+async fn test_validate_unsigned() {
+    let url = "ws://127.0.0.1:9944".to_string();
+    let client = crate::DeepSafeSubClient::new_from_signer(&url, None, None, None)
+        .await
+        .unwrap();
+    // invalid proofs are rejected with a custom error of the runtime
+    let call = crate::deepsafe::tx()
+        .committee()
+        .enter_epoch(u64::MAX, vec![(vec![], vec![], vec![])]);
+    match client.validate_unsigned(&call).await.unwrap() {
+        TxValidity::Invalid(invalid) => println!("invalid: {invalid:?}"),
+        TxValidity::Valid(valid) => panic!("unexpected valid tx: {valid:?}"),
+    }
+}
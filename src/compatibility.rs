@@ -0,0 +1,260 @@
+//! Compatibility report between the live node metadata and the compiled 'metadata.scale'.
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, Variant};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use subxt::Metadata;
+
+/// The metadata which the static 'deepsafe' module is generated from.
+pub const COMPILED_METADATA: &[u8] = include_bytes!("../metadata.scale");
+
+/// Items of one pallet which are missing or changed on the node,
+/// items only existing on the node are ignored because nothing compiled uses them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PalletIncompatibility {
+    pub pallet: String,
+    // the whole pallet is missing on the node
+    pub missing: bool,
+    pub calls: Vec<String>,
+    pub storage: Vec<String>,
+    pub events: Vec<String>,
+    pub constants: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    pub pallets: Vec<PalletIncompatibility>,
+}
+
+impl CompatibilityReport {
+    pub fn is_compatible(&self) -> bool {
+        self.pallets.is_empty()
+    }
+
+    pub fn pallet(&self, pallet: &str) -> Option<&PalletIncompatibility> {
+        self.pallets.iter().find(|p| p.pallet == pallet)
+    }
+
+    pub fn is_call_compatible(&self, pallet: &str, call: &str) -> bool {
+        match self.pallet(pallet) {
+            Some(p) => !p.missing && !p.calls.iter().any(|c| c == call),
+            None => true,
+        }
+    }
+
+    /// Return error with all incompatible calls among '(pallet, call)',
+    /// i.e. a service could refuse to sign at startup.
+    pub fn ensure_calls_compatible(&self, calls: &[(&str, &str)]) -> Result<(), String> {
+        let incompatible = calls
+            .iter()
+            .filter(|(pallet, call)| !self.is_call_compatible(pallet, call))
+            .map(|(pallet, call)| format!("{pallet}::{call}"))
+            .collect::<Vec<_>>();
+        if incompatible.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "incompatible calls with node metadata: {}",
+                incompatible.join(", ")
+            ))
+        }
+    }
+}
+
+/// Compare 'live' node metadata with the 'compiled' one item by item.
+pub fn compare_metadata(compiled: &Metadata, live: &Metadata) -> CompatibilityReport {
+    let mut pallets = Vec::new();
+    for compiled_pallet in compiled.pallets() {
+        let name = compiled_pallet.name();
+        let mut incompatibility = PalletIncompatibility {
+            pallet: name.to_string(),
+            ..Default::default()
+        };
+        let live_pallet = match live.pallet_by_name(name) {
+            Some(pallet) => pallet,
+            None => {
+                incompatibility.missing = true;
+                pallets.push(incompatibility);
+                continue;
+            }
+        };
+
+        for call in compiled_pallet.call_variants().unwrap_or_default() {
+            if compiled_pallet.call_hash(&call.name) != live_pallet.call_hash(&call.name) {
+                incompatibility.calls.push(call.name.clone());
+            }
+        }
+        if let Some(storage) = compiled_pallet.storage() {
+            for entry in storage.entries() {
+                if compiled_pallet.storage_hash(entry.name())
+                    != live_pallet.storage_hash(entry.name())
+                {
+                    incompatibility.storage.push(entry.name().to_string());
+                }
+            }
+        }
+        for event in compiled_pallet.event_variants().unwrap_or_default() {
+            let live_event = live_pallet
+                .event_variants()
+                .and_then(|events| events.iter().find(|e| e.name == event.name));
+            let compatible = match live_event {
+                Some(live_event) => {
+                    variant_hash(compiled.types(), event) == variant_hash(live.types(), live_event)
+                }
+                None => false,
+            };
+            if !compatible {
+                incompatibility.events.push(event.name.clone());
+            }
+        }
+        for constant in compiled_pallet.constants() {
+            if compiled_pallet.constant_hash(constant.name())
+                != live_pallet.constant_hash(constant.name())
+            {
+                incompatibility.constants.push(constant.name().to_string());
+            }
+        }
+
+        if !incompatibility.calls.is_empty()
+            || !incompatibility.storage.is_empty()
+            || !incompatibility.events.is_empty()
+            || !incompatibility.constants.is_empty()
+        {
+            pallets.push(incompatibility);
+        }
+    }
+    CompatibilityReport { pallets }
+}
+
+// hash of the variant shape, type ids differ between registries so types are hashed by structure
+fn variant_hash(registry: &PortableRegistry, variant: &Variant<PortableForm>) -> [u8; 32] {
+    let mut bytes = variant.name.as_bytes().to_vec();
+    bytes.push(variant.index);
+    for field in &variant.fields {
+        bytes.extend(field.name.as_deref().unwrap_or_default().as_bytes());
+        bytes.extend(type_hash(registry, field.ty.id, &mut HashSet::new()));
+    }
+    sp_core::blake2_256(&bytes)
+}
+
+fn type_hash(registry: &PortableRegistry, id: u32, visited: &mut HashSet<u32>) -> [u8; 32] {
+    // recursive types are hashed as empty when seen again
+    if !visited.insert(id) {
+        return [0u8; 32];
+    }
+    let mut bytes = Vec::new();
+    if let Some(ty) = registry.resolve(id) {
+        match &ty.type_def {
+            TypeDef::Composite(composite) => {
+                bytes.push(0);
+                for field in &composite.fields {
+                    bytes.extend(field.name.as_deref().unwrap_or_default().as_bytes());
+                    bytes.extend(type_hash(registry, field.ty.id, visited));
+                }
+            }
+            TypeDef::Variant(variants) => {
+                bytes.push(1);
+                for variant in &variants.variants {
+                    bytes.extend(variant.name.as_bytes());
+                    bytes.push(variant.index);
+                    for field in &variant.fields {
+                        bytes.extend(field.name.as_deref().unwrap_or_default().as_bytes());
+                        bytes.extend(type_hash(registry, field.ty.id, visited));
+                    }
+                }
+            }
+            TypeDef::Sequence(sequence) => {
+                bytes.push(2);
+                bytes.extend(type_hash(registry, sequence.type_param.id, visited));
+            }
+            TypeDef::Array(array) => {
+                bytes.push(3);
+                bytes.extend(array.len.to_le_bytes());
+                bytes.extend(type_hash(registry, array.type_param.id, visited));
+            }
+            TypeDef::Tuple(tuple) => {
+                bytes.push(4);
+                for field in &tuple.fields {
+                    bytes.extend(type_hash(registry, field.id, visited));
+                }
+            }
+            TypeDef::Primitive(primitive) => {
+                bytes.push(5);
+                bytes.extend(format!("{primitive:?}").as_bytes());
+            }
+            TypeDef::Compact(compact) => {
+                bytes.push(6);
+                bytes.extend(type_hash(registry, compact.type_param.id, visited));
+            }
+            TypeDef::BitSequence(bits) => {
+                bytes.push(7);
+                bytes.extend(type_hash(registry, bits.bit_store_type.id, visited));
+                bytes.extend(type_hash(registry, bits.bit_order_type.id, visited));
+            }
+        }
+    }
+    visited.remove(&id);
+    sp_core::blake2_256(&bytes)
+}
+
+#[test]
+fn test_compiled_metadata_compatible_with_itself() {
+    use codec::Decode;
+
+    let compiled = Metadata::decode(&mut &COMPILED_METADATA[..]).unwrap();
+    let live = Metadata::decode(&mut &COMPILED_METADATA[..]).unwrap();
+    let report = compare_metadata(&compiled, &live);
+    assert!(report.is_compatible());
+    assert!(report
+        .ensure_calls_compatible(&[("Channel", "create_channel")])
+        .is_ok());
+}
+
+#[test]
+fn test_report_removed_call_and_storage() {
+    use codec::{Decode, Encode};
+    use subxt::ext::frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+
+    let compiled = Metadata::decode(&mut &COMPILED_METADATA[..]).unwrap();
+    let mut prefixed = RuntimeMetadataPrefixed::decode(&mut &COMPILED_METADATA[..]).unwrap();
+    let metadata = match &mut prefixed.1 {
+        RuntimeMetadata::V14(metadata) => metadata,
+        _ => panic!("compiled metadata should be v14"),
+    };
+    // the node drops 'Channel::sync_status' and 'Channel::TxMessages'
+    let pallet = metadata
+        .pallets
+        .iter_mut()
+        .find(|pallet| pallet.name == "Channel")
+        .unwrap();
+    pallet
+        .storage
+        .as_mut()
+        .unwrap()
+        .entries
+        .retain(|entry| entry.name != "TxMessages");
+    let call_ty = pallet.calls.as_ref().unwrap().ty.id;
+    let call_ty = metadata
+        .types
+        .types
+        .iter_mut()
+        .find(|ty| ty.id == call_ty)
+        .unwrap();
+    match &mut call_ty.ty.type_def {
+        TypeDef::Variant(calls) => calls.variants.retain(|call| call.name != "sync_status"),
+        _ => panic!("calls should be a variant"),
+    }
+    let live = Metadata::decode(&mut &prefixed.encode()[..]).unwrap();
+
+    let report = compare_metadata(&compiled, &live);
+    assert!(!report.is_compatible());
+    let channel = report.pallet("Channel").unwrap();
+    assert!(!channel.missing);
+    assert_eq!(channel.calls, vec!["sync_status".to_string()]);
+    assert_eq!(channel.storage, vec!["TxMessages".to_string()]);
+    assert!(channel.events.is_empty());
+    assert!(report.pallet("Committee").is_none());
+    assert!(report.is_call_compatible("Channel", "create_channel"));
+    assert!(report
+        .ensure_calls_compatible(&[("Channel", "create_channel"), ("Channel", "sync_status")])
+        .is_err());
+}
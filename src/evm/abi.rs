@@ -0,0 +1,360 @@
+//! Solidity abi helpers for the input and returned data of evm calls.
+use crate::deepsafe::runtime_types::ethereum::log::Log;
+pub use precompile_utils::prelude::{Address, Codec, UnboundedBytes};
+use precompile_utils::solidity::{decode_arguments, encode_arguments};
+use serde_json::{Map, Value as JsonValue};
+use sp_core::{H160, H256, U256};
+
+/// keccak_256("Error(string)".as_bytes())[..4]
+pub const ERROR_SELECTOR: [u8; 4] = [8, 195, 121, 160];
+/// keccak_256("Panic(uint256)".as_bytes())[..4]
+pub const PANIC_SELECTOR: [u8; 4] = [78, 72, 123, 113];
+
+/// 'keccak_256(signature)[..4]' of the solidity signature, i.e. 'balanceOf(address)'.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = sp_core::keccak_256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Input of calling 'signature' with abi encoded 'args', a tuple for multiple arguments.
+/// The arguments are a top-level tuple, without the offset word of a nested dynamic tuple.
+pub fn encode_input<T: Codec>(signature: &str, args: T) -> Vec<u8> {
+    let mut input = selector(signature).to_vec();
+    input.extend(encode_arguments(args));
+    input
+}
+
+/// Decode the returned data as 'T', i.e. '(U256, Address)' for 'returns (uint256, address)'.
+pub fn decode_output<T: Codec>(output: &[u8]) -> Result<T, String> {
+    decode_arguments::<T>(output).map_err(|e| format!("decode evm output failed: {e:?}"))
+}
+
+/// Reason of 'revert("...")' or 'Panic(code)' in the returned data of a reverted call.
+pub fn revert_reason(output: &[u8]) -> Option<String> {
+    let (selector, data) = (output.get(..4)?, &output[4..]);
+    if selector == ERROR_SELECTOR {
+        let reason = Vec::<u8>::from(decode_output::<UnboundedBytes>(data).ok()?);
+        Some(String::from_utf8_lossy(&reason).into_owned())
+    } else if selector == PANIC_SELECTOR {
+        let code = decode_output::<U256>(data).ok()?;
+        Some(format!("panic code 0x{code:x}"))
+    } else {
+        None
+    }
+}
+
+/// Solidity type of an event parameter, arrays and tuples are not supported.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AbiType {
+    Address,
+    Bool,
+    Uint(usize),
+    Int(usize),
+    FixedBytes(usize),
+    Bytes,
+    String,
+}
+
+impl AbiType {
+    pub fn parse(ty: &str) -> Result<Self, String> {
+        let size = |s: &str, default: usize| match s {
+            "" => Ok(default),
+            s => s
+                .parse::<usize>()
+                .map_err(|e| format!("invalid abi type {ty}: {e}")),
+        };
+        Ok(match ty {
+            "address" => AbiType::Address,
+            "bool" => AbiType::Bool,
+            "string" => AbiType::String,
+            "bytes" => AbiType::Bytes,
+            _ if ty.starts_with("uint") => AbiType::Uint(size(&ty[4..], 256)?),
+            _ if ty.starts_with("int") => AbiType::Int(size(&ty[3..], 256)?),
+            _ if ty.starts_with("bytes") => AbiType::FixedBytes(size(&ty[5..], 32)?),
+            _ => return Err(format!("unsupported abi type {ty}")),
+        })
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            AbiType::Address => "address".to_string(),
+            AbiType::Bool => "bool".to_string(),
+            AbiType::Uint(bits) => format!("uint{bits}"),
+            AbiType::Int(bits) => format!("int{bits}"),
+            AbiType::FixedBytes(len) => format!("bytes{len}"),
+            AbiType::Bytes => "bytes".to_string(),
+            AbiType::String => "string".to_string(),
+        }
+    }
+
+    fn is_dynamic(&self) -> bool {
+        matches!(self, AbiType::Bytes | AbiType::String)
+    }
+
+    // json of a static value in one word
+    fn word_to_json(&self, word: &[u8]) -> JsonValue {
+        let value = U256::from_big_endian(word);
+        match self {
+            AbiType::Address => JsonValue::String(format!("0x{}", hex::encode(&word[12..]))),
+            AbiType::Bool => JsonValue::Bool(!value.is_zero()),
+            AbiType::Uint(_) => u256_to_json(value, false),
+            AbiType::Int(_) if word[0] & 0x80 != 0 => {
+                u256_to_json((!value).overflowing_add(U256::one()).0, true)
+            }
+            AbiType::Int(_) => u256_to_json(value, false),
+            AbiType::FixedBytes(len) => {
+                JsonValue::String(format!("0x{}", hex::encode(&word[..*len.min(&32)])))
+            }
+            AbiType::Bytes | AbiType::String => {
+                JsonValue::String(format!("0x{}", hex::encode(word)))
+            }
+        }
+    }
+}
+
+// numbers exceeding 64 bits are kept as decimal string to avoid precision loss in json
+fn u256_to_json(value: U256, negative: bool) -> JsonValue {
+    match (u64::try_from(value), negative) {
+        (Ok(n), false) => JsonValue::from(n),
+        (Ok(n), true) if n <= i64::MAX as u64 + 1 => JsonValue::from(-(n as i128) as i64),
+        (_, false) => JsonValue::String(value.to_string()),
+        (_, true) => JsonValue::String(format!("-{value}")),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventParam {
+    pub name: String,
+    pub kind: AbiType,
+    pub indexed: bool,
+}
+
+/// Solidity event declaration to decode evm logs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventAbi {
+    pub name: String,
+    pub inputs: Vec<EventParam>,
+}
+
+/// Evm log decoded by 'EventAbi', the params are keyed by name or index if unnamed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedLog {
+    pub address: H160,
+    pub name: String,
+    pub params: Map<String, JsonValue>,
+}
+
+impl EventAbi {
+    /// Parse the declaration, i.e. 'Transfer(address indexed from, address indexed to, uint256 value)'.
+    pub fn parse(declaration: &str) -> Result<Self, String> {
+        let declaration = declaration.trim().trim_start_matches("event ");
+        let (name, rest) = declaration
+            .split_once('(')
+            .ok_or_else(|| format!("invalid event declaration {declaration}"))?;
+        let params = rest
+            .trim_end_matches(';')
+            .trim_end()
+            .strip_suffix(')')
+            .ok_or_else(|| format!("invalid event declaration {declaration}"))?;
+        let mut inputs = vec![];
+        for (i, param) in params.split(',').map(str::trim).enumerate() {
+            if param.is_empty() {
+                continue;
+            }
+            let mut words = param.split_whitespace();
+            let kind = AbiType::parse(words.next().unwrap_or_default())?;
+            let (indexed, name) = match (words.next(), words.next()) {
+                (Some("indexed"), name) => (true, name),
+                (name, None) => (false, name),
+                _ => return Err(format!("invalid event param {param}")),
+            };
+            inputs.push(EventParam {
+                name: name.map(str::to_string).unwrap_or_else(|| i.to_string()),
+                kind,
+                indexed,
+            });
+        }
+        Ok(Self {
+            name: name.trim().to_string(),
+            inputs,
+        })
+    }
+
+    /// Canonical signature, i.e. 'Transfer(address,address,uint256)'.
+    pub fn signature(&self) -> String {
+        let types = self
+            .inputs
+            .iter()
+            .map(|param| param.kind.name())
+            .collect::<Vec<_>>();
+        format!("{}({})", self.name, types.join(","))
+    }
+
+    /// The first topic of the logs of the event.
+    pub fn topic(&self) -> H256 {
+        sp_core::keccak_256(self.signature().as_bytes()).into()
+    }
+
+    /// Decode the log, 'None' if the log isn't emitted by the event. Indexed dynamic params
+    /// are only the keccak hash in the topic.
+    pub fn decode(&self, log: &Log) -> Result<Option<DecodedLog>, String> {
+        if log.topics.first() != Some(&self.topic()) {
+            return Ok(None);
+        }
+        let word = |data: &[u8], offset: usize| {
+            data.get(offset..offset + 32)
+                .ok_or_else(|| format!("log data of {} is too short", self.name))
+        };
+        let (mut topic, mut head) = (1, 0);
+        let mut params = Map::new();
+        for param in &self.inputs {
+            let value = if param.indexed {
+                let value = log
+                    .topics
+                    .get(topic)
+                    .ok_or_else(|| format!("missing topic of {}", param.name))?;
+                topic += 1;
+                match param.kind.is_dynamic() {
+                    true => JsonValue::String(format!("0x{}", hex::encode(value))),
+                    false => param.kind.word_to_json(&value[..]),
+                }
+            } else {
+                let value = word(&log.data, head)?;
+                head += 32;
+                if param.kind.is_dynamic() {
+                    let offset = U256::from_big_endian(value).low_u64() as usize;
+                    let len = U256::from_big_endian(word(&log.data, offset)?).low_u64() as usize;
+                    let bytes = log
+                        .data
+                        .get(offset + 32..offset + 32 + len)
+                        .ok_or_else(|| format!("log data of {} is too short", param.name))?;
+                    match param.kind {
+                        AbiType::String => {
+                            JsonValue::String(String::from_utf8_lossy(bytes).into_owned())
+                        }
+                        _ => JsonValue::String(format!("0x{}", hex::encode(bytes))),
+                    }
+                } else {
+                    param.kind.word_to_json(value)
+                }
+            };
+            params.insert(param.name.clone(), value);
+        }
+        Ok(Some(DecodedLog {
+            address: log.address,
+            name: self.name.clone(),
+            params,
+        }))
+    }
+}
+
+/// Decode the logs emitted by any of 'abis', other logs are skipped.
+pub fn decode_logs(logs: &[Log], abis: &[EventAbi]) -> Result<Vec<DecodedLog>, String> {
+    let mut decoded = vec![];
+    for log in logs {
+        for abi in abis {
+            if let Some(log) = abi.decode(log)? {
+                decoded.push(log);
+                break;
+            }
+        }
+    }
+    Ok(decoded)
+}
+
+#[test]
+fn test_abi_helpers() {
+    use precompile_utils::solidity::codec::Writer;
+    use sp_core::H160;
+
+    assert_eq!(selector("transfer(address,uint256)"), [169, 5, 156, 187]);
+    assert_eq!(selector("Error(string)"), ERROR_SELECTOR);
+    assert_eq!(selector("Panic(uint256)"), PANIC_SELECTOR);
+
+    let input = encode_input(
+        "transfer(address,uint256)",
+        (Address(H160::repeat_byte(1)), U256::from(7)),
+    );
+    assert_eq!(input.len(), 4 + 64);
+    let (to, amount) = decode_output::<(Address, U256)>(&input[4..]).unwrap();
+    assert_eq!(to, Address(H160::repeat_byte(1)));
+    assert_eq!(amount, U256::from(7));
+
+    let reverted = Writer::new_with_selector(u32::from_be_bytes(ERROR_SELECTOR))
+        .write(UnboundedBytes::from(b"not allowed".to_vec()))
+        .build();
+    assert_eq!(revert_reason(&reverted), Some("not allowed".to_string()));
+    assert_eq!(revert_reason(&input), None);
+}
+
+#[test]
+fn test_abi_dynamic_arguments() {
+    let input = encode_input(
+        "f(bytes,uint256)",
+        (UnboundedBytes::from(b"hi".to_vec()), U256::from(7)),
+    );
+    // selector, offset of 'bytes', 'uint256', length and data of 'bytes', no outer offset
+    assert_eq!(input.len(), 4 + 32 * 4);
+    assert_eq!(&input[..4], &selector("f(bytes,uint256)"));
+    assert_eq!(U256::from_big_endian(&input[4..36]), U256::from(64));
+    assert_eq!(U256::from_big_endian(&input[36..68]), U256::from(7));
+    assert_eq!(U256::from_big_endian(&input[68..100]), U256::from(2));
+    assert_eq!(&input[100..102], b"hi");
+
+    let (bytes, value) = decode_output::<(UnboundedBytes, U256)>(&input[4..]).unwrap();
+    assert_eq!(Vec::<u8>::from(bytes), b"hi".to_vec());
+    assert_eq!(value, U256::from(7));
+
+    // returned data of 'returns (uint256, bytes)'
+    let mut output = vec![0u8; 32 * 4];
+    output[31] = 7;
+    output[63] = 64;
+    output[95] = 3;
+    output[96..99].copy_from_slice(b"abc");
+    let (value, bytes) = decode_output::<(U256, UnboundedBytes)>(&output).unwrap();
+    assert_eq!(value, U256::from(7));
+    assert_eq!(Vec::<u8>::from(bytes), b"abc".to_vec());
+}
+
+#[test]
+fn test_decode_event_log() {
+    use precompile_utils::solidity::codec::Writer;
+
+    let abi = EventAbi::parse(
+        "event Transfer(address indexed from, address indexed to, uint256 value, string memo, int32)",
+    )
+    .unwrap();
+    assert_eq!(
+        abi.signature(),
+        "Transfer(address,address,uint256,string,int32)"
+    );
+    let transfer = EventAbi::parse("Transfer(address indexed, address indexed, uint256)").unwrap();
+    assert_eq!(
+        hex::encode(transfer.topic()),
+        "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+    );
+
+    let mut from = H256::zero();
+    from.0[12..].copy_from_slice(&[1u8; 20]);
+    let data = Writer::new()
+        .write(U256::from(7))
+        .write(UnboundedBytes::from(b"hi".to_vec()))
+        .write(U256::MAX)
+        .build();
+    let log = Log {
+        address: H160::repeat_byte(9),
+        topics: vec![abi.topic(), from, H256::zero()],
+        data,
+    };
+    let decoded = decode_logs(&[log.clone()], &[transfer, abi]).unwrap();
+    assert_eq!(decoded.len(), 1);
+    assert_eq!(decoded[0].name, "Transfer");
+    assert_eq!(decoded[0].address, H160::repeat_byte(9));
+    assert_eq!(
+        decoded[0].params["from"],
+        JsonValue::String(format!("0x{}", hex::encode([1u8; 20])))
+    );
+    assert_eq!(decoded[0].params["value"], JsonValue::from(7u64));
+    assert_eq!(decoded[0].params["memo"], JsonValue::from("hi"));
+    assert_eq!(decoded[0].params["4"], JsonValue::from(-1i64));
+}
@@ -0,0 +1,236 @@
+//! EIP-1559 fee and gas estimation from the on-chain evm state.
+//!
+//! The runtime has no 'BaseFee' pallet, so the base fee is the minimum gas price returned by
+//! runtime api 'EthereumRuntimeRPCApi_gas_price', which is also the base fee once the pallet is added.
+use crate::deepsafe::runtime_types::ethereum::block::Block;
+use crate::deepsafe::runtime_types::ethereum::transaction::TransactionV2;
+use crate::precompile::DEFAULT_MAX_PRIORITY_FEE_PER_GAS;
+use crate::query::ethereum::{current_block, estimate_gas, gas_price};
+use crate::types::EvmCallRequest;
+use crate::{At, DeepSafeSubClient};
+use sp_core::U256;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EvmFeeConfig {
+    // percent of the base fee reserved in 'max_fee_per_gas', i.e. 200 keeps the tx valid
+    // if the base fee doubles before it is included
+    pub base_fee_percent: u32,
+    // percentile of the priority fees paid by tx in the current block
+    pub priority_fee_percentile: u8,
+    // used when no tx in the current block paid a higher priority fee
+    pub min_priority_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    // percent of the estimated gas used as the gas limit
+    pub gas_limit_percent: u32,
+    // cap of the gas limit, also used as the gas limit if the gas isn't estimated
+    pub max_gas_limit: U256,
+}
+
+impl EvmFeeConfig {
+    /// Gas limit with 'gas_limit_percent' of the estimated gas, capped by 'max_gas_limit'.
+    pub fn gas_limit(&self, estimated_gas: Option<U256>) -> U256 {
+        estimated_gas
+            .map(|gas| gas.saturating_mul(U256::from(self.gas_limit_percent)) / 100)
+            .unwrap_or(self.max_gas_limit)
+            .min(self.max_gas_limit)
+    }
+}
+
+impl Default for EvmFeeConfig {
+    fn default() -> Self {
+        Self {
+            base_fee_percent: 200,
+            priority_fee_percentile: 50,
+            min_priority_fee_per_gas: U256::from(DEFAULT_MAX_PRIORITY_FEE_PER_GAS),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_limit_percent: 120,
+            max_gas_limit: U256::from(50_000_000u64),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvmFees {
+    pub base_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+}
+
+impl EvmFees {
+    /// Set fees and gas limit of the EIP-1559 message.
+    pub fn apply_to(&self, tx: &mut ethereum::EIP1559TransactionMessage) {
+        tx.max_priority_fee_per_gas = self.max_priority_fee_per_gas;
+        tx.max_fee_per_gas = self.max_fee_per_gas;
+        tx.gas_limit = self.gas_limit;
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvmFeeEstimator {
+    pub config: EvmFeeConfig,
+}
+
+impl EvmFeeEstimator {
+    pub fn new(config: EvmFeeConfig) -> Self {
+        Self { config }
+    }
+
+    /// Fees from the base fee and the current block, the gas limit is 'max_gas_limit'.
+    pub async fn fees(
+        &self,
+        sub_client: &DeepSafeSubClient,
+        at_block: At,
+    ) -> Result<EvmFees, subxt::Error> {
+        let base_fee = gas_price(sub_client, at_block).await?;
+        let paid = current_block(sub_client, at_block)
+            .await?
+            .map(|block| paid_priority_fees(&block, base_fee))
+            .unwrap_or_default();
+        Ok(compute_fees(&self.config, base_fee, paid, None))
+    }
+
+    /// Same as 'fees' but the gas limit is estimated by executing 'request'.
+    pub async fn estimate(
+        &self,
+        sub_client: &DeepSafeSubClient,
+        request: &EvmCallRequest,
+        at_block: At,
+    ) -> Result<EvmFees, subxt::Error> {
+        let mut request = request.clone();
+        request.gas_limit.get_or_insert(self.config.max_gas_limit);
+        let gas = estimate_gas(sub_client, &request, at_block).await?;
+        let fees = self.fees(sub_client, at_block).await?;
+        Ok(EvmFees {
+            gas_limit: self.config.gas_limit(Some(gas)),
+            ..fees
+        })
+    }
+
+    /// Apply the estimated fees to the EIP-1559 message. The gas is estimated as sent by 'from',
+    /// or the gas limit of 'tx' is kept for unsigned tx without 'from'.
+    pub async fn apply(
+        &self,
+        sub_client: &DeepSafeSubClient,
+        from: Option<sp_core::H160>,
+        tx: &mut ethereum::EIP1559TransactionMessage,
+    ) -> Result<EvmFees, subxt::Error> {
+        let fees = match from {
+            Some(from) => {
+                let request = EvmCallRequest {
+                    from,
+                    to: match tx.action {
+                        ethereum::TransactionAction::Call(to) => Some(to),
+                        ethereum::TransactionAction::Create => None,
+                    },
+                    data: tx.input.clone(),
+                    value: tx.value,
+                    ..Default::default()
+                };
+                self.estimate(sub_client, &request, At::Latest).await?
+            }
+            None => EvmFees {
+                gas_limit: tx.gas_limit,
+                ..self.fees(sub_client, At::Latest).await?
+            },
+        };
+        fees.apply_to(tx);
+        Ok(fees)
+    }
+}
+
+/// Apply fees by the 'evm_fee_estimator' of the client if set, the fees of 'tx' are kept if
+/// the estimator isn't set or fails.
+pub async fn apply_client_fees(
+    sub_client: &DeepSafeSubClient,
+    from: Option<sp_core::H160>,
+    tx: &mut ethereum::EIP1559TransactionMessage,
+) {
+    if let Some(estimator) = &sub_client.evm_fee_estimator {
+        if let Err(e) = estimator.apply(sub_client, from, tx).await {
+            log::warn!(target: "subxt", "estimate evm fees failed, use the default fees: {:?}", e);
+        }
+    }
+}
+
+/// Priority fee per gas actually paid by each tx of the block with 'base_fee'.
+pub fn paid_priority_fees(block: &Block<TransactionV2>, base_fee: U256) -> Vec<U256> {
+    block
+        .transactions
+        .iter()
+        .map(|tx| match tx {
+            TransactionV2::Legacy(tx) => U256(tx.gas_price.0).saturating_sub(base_fee),
+            TransactionV2::EIP2930(tx) => U256(tx.gas_price.0).saturating_sub(base_fee),
+            TransactionV2::EIP1559(tx) => std::cmp::min(
+                U256(tx.max_priority_fee_per_gas.0),
+                U256(tx.max_fee_per_gas.0).saturating_sub(base_fee),
+            ),
+        })
+        .collect()
+}
+
+/// Fees by 'config' from the base fee, the paid priority fees and the estimated gas.
+pub fn compute_fees(
+    config: &EvmFeeConfig,
+    base_fee: U256,
+    mut paid_priority_fees: Vec<U256>,
+    estimated_gas: Option<U256>,
+) -> EvmFees {
+    paid_priority_fees.sort();
+    let mut priority_fee = match paid_priority_fees.len() {
+        0 => config.min_priority_fee_per_gas,
+        len => std::cmp::max(
+            paid_priority_fees[(len - 1) * config.priority_fee_percentile.min(100) as usize / 100],
+            config.min_priority_fee_per_gas,
+        ),
+    };
+    if let Some(cap) = config.max_priority_fee_per_gas {
+        priority_fee = priority_fee.min(cap);
+    }
+    let reserved_base_fee = base_fee.saturating_mul(U256::from(config.base_fee_percent)) / 100;
+    let mut max_fee = reserved_base_fee.saturating_add(priority_fee);
+    if let Some(cap) = config.max_fee_per_gas {
+        max_fee = max_fee.min(cap);
+    }
+    EvmFees {
+        base_fee_per_gas: base_fee,
+        max_priority_fee_per_gas: priority_fee.min(max_fee),
+        max_fee_per_gas: max_fee,
+        gas_limit: config.gas_limit(estimated_gas),
+    }
+}
+
+#[test]
+fn test_compute_evm_fees() {
+    let config = EvmFeeConfig {
+        min_priority_fee_per_gas: U256::from(10),
+        max_gas_limit: U256::from(1_000_000),
+        ..Default::default()
+    };
+    let fees = compute_fees(&config, U256::from(100), vec![], None);
+    assert_eq!(fees.max_priority_fee_per_gas, U256::from(10));
+    assert_eq!(fees.max_fee_per_gas, U256::from(210));
+    assert_eq!(fees.gas_limit, U256::from(1_000_000));
+
+    let paid = [50, 5, 30, 20, 40].into_iter().map(U256::from).collect();
+    let fees = compute_fees(&config, U256::from(100), paid, Some(U256::from(21_000)));
+    assert_eq!(fees.max_priority_fee_per_gas, U256::from(30));
+    assert_eq!(fees.gas_limit, U256::from(25_200));
+
+    let capped = EvmFeeConfig {
+        max_fee_per_gas: Some(U256::from(150)),
+        max_priority_fee_per_gas: Some(U256::from(20)),
+        ..config
+    };
+    let fees = compute_fees(
+        &capped,
+        U256::from(100),
+        vec![U256::from(30)],
+        Some(U256::from(u64::MAX)),
+    );
+    assert_eq!(fees.max_priority_fee_per_gas, U256::from(20));
+    assert_eq!(fees.max_fee_per_gas, U256::from(150));
+    assert_eq!(fees.gas_limit, U256::from(1_000_000));
+}
@@ -0,0 +1,6 @@
+//! Helpers for evm tx on DeepSafe, which are built and queried through the substrate node.
+pub mod abi;
+pub mod fee;
+pub mod nonce;
+pub mod receipt;
+pub mod tx;
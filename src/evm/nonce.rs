@@ -0,0 +1,227 @@
+//! Nonce management of signed evm tx, keyed by the evm address of the sender.
+//!
+//! Pending evm tx are recorded here instead of the 'call_cache' of substrate extrinsics,
+//! and are replaced with higher fees when they are stuck.
+use crate::evm::tx::{EvmTxBuilder, EvmTxFees};
+use sp_core::{H160, H256, U256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+
+/// Percent of the fees raised for each replacement of a stuck tx.
+pub const REPLACEMENT_BUMP_PERCENT: u32 = 10;
+
+/// Default of how long a pending evm tx waits before it's replaced as stuck.
+pub const STUCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default of the max times a stuck evm tx is replaced.
+pub const MAX_REPLACEMENTS: u32 = 5;
+
+/// Signed evm tx not yet included by the chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingEvmTx {
+    pub nonce: U256,
+    // ethereum tx hash
+    pub hash: H256,
+    pub builder: EvmTxBuilder,
+    pub submitted_at: SystemTime,
+    // times replaced with higher fees
+    pub replaced: u32,
+}
+
+#[derive(Debug, Default)]
+struct EvmAccountNonce {
+    next_nonce: U256,
+    pending: BTreeMap<U256, PendingEvmTx>,
+}
+
+/// A pending tx is stuck once it's not included 'stuck_timeout' after the last submission,
+/// and is replaced at most 'max_replacements' times.
+#[derive(Clone, Debug)]
+pub struct EvmNonceTracker {
+    accounts: Arc<Mutex<HashMap<H160, EvmAccountNonce>>>,
+    stuck_timeout: Duration,
+    max_replacements: u32,
+}
+
+impl Default for EvmNonceTracker {
+    fn default() -> Self {
+        Self::with_limits(STUCK_TIMEOUT, MAX_REPLACEMENTS)
+    }
+}
+
+impl EvmNonceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limits(stuck_timeout: Duration, max_replacements: u32) -> Self {
+        Self {
+            accounts: Default::default(),
+            stuck_timeout,
+            max_replacements,
+        }
+    }
+
+    /// Pending tx of 'address' not lower than 'chain_nonce', waiting longer than the timeout
+    /// and not yet replaced 'max_replacements' times.
+    pub async fn stuck(
+        &self,
+        address: H160,
+        chain_nonce: U256,
+        now: SystemTime,
+    ) -> Vec<PendingEvmTx> {
+        self.pending(address)
+            .await
+            .into_iter()
+            .filter(|tx| {
+                tx.nonce >= chain_nonce
+                    && tx.replaced < self.max_replacements
+                    && now.duration_since(tx.submitted_at).unwrap_or_default() >= self.stuck_timeout
+            })
+            .collect()
+    }
+
+    /// Reserve the next nonce of 'address', not lower than 'chain_nonce'. The pending tx
+    /// with lower nonce are included and dropped from tracking.
+    pub async fn reserve(&self, address: H160, chain_nonce: U256) -> U256 {
+        let mut accounts = self.accounts.lock().await;
+        let account = accounts.entry(address).or_default();
+        account.pending = account.pending.split_off(&chain_nonce);
+        let nonce = std::cmp::max(account.next_nonce, chain_nonce);
+        account.next_nonce = nonce + 1;
+        nonce
+    }
+
+    /// Give back the reserved nonce when the tx isn't submitted, only the latest reserved
+    /// one can be given back.
+    pub async fn release(&self, address: H160, nonce: U256) {
+        if let Some(account) = self.accounts.lock().await.get_mut(&address) {
+            if account.next_nonce == nonce + 1 && !account.pending.contains_key(&nonce) {
+                account.next_nonce = nonce;
+            }
+        }
+    }
+
+    /// Record the submitted tx, which replaces the pending tx with the same nonce.
+    pub async fn record(&self, address: H160, tx: PendingEvmTx) {
+        let mut accounts = self.accounts.lock().await;
+        let account = accounts.entry(address).or_default();
+        account.next_nonce = std::cmp::max(account.next_nonce, tx.nonce + 1);
+        account.pending.insert(tx.nonce, tx);
+    }
+
+    /// Next nonce to reserve of 'address', 'None' if the address isn't tracked.
+    pub async fn next_nonce(&self, address: H160) -> Option<U256> {
+        self.accounts
+            .lock()
+            .await
+            .get(&address)
+            .map(|account| account.next_nonce)
+    }
+
+    /// Pending tx of 'address' ordered by nonce.
+    pub async fn pending(&self, address: H160) -> Vec<PendingEvmTx> {
+        self.accounts
+            .lock()
+            .await
+            .get(&address)
+            .map(|account| account.pending.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forget the reserved and pending nonce of 'address', i.e. after the account is used by others.
+    pub async fn reset(&self, address: H160) {
+        self.accounts.lock().await.remove(&address);
+    }
+}
+
+/// Same tx with the fees raised by 'bump_percent' at least 1 wei, so that the tx pool accepts
+/// it as the replacement of the pending one.
+pub fn replacement(tx: &PendingEvmTx, bump_percent: u32) -> EvmTxBuilder {
+    let bump = |fee: U256| {
+        let raised = fee.saturating_mul(U256::from(100 + bump_percent)) / 100;
+        std::cmp::max(raised, fee.saturating_add(U256::one()))
+    };
+    let mut builder = tx.builder.clone();
+    builder.fees = match builder.fees {
+        EvmTxFees::Legacy(gas_price) => EvmTxFees::Legacy(bump(gas_price)),
+        EvmTxFees::EIP2930(gas_price) => EvmTxFees::EIP2930(bump(gas_price)),
+        EvmTxFees::EIP1559(max_priority_fee_per_gas, max_fee_per_gas) => {
+            EvmTxFees::EIP1559(bump(max_priority_fee_per_gas), bump(max_fee_per_gas))
+        }
+        EvmTxFees::Default => EvmTxFees::EIP1559(
+            bump(U256::from(
+                crate::precompile::DEFAULT_MAX_PRIORITY_FEE_PER_GAS,
+            )),
+            bump(U256::from(crate::precompile::DEFAULT_MAX_FEE_PER_GAS)),
+        ),
+    };
+    builder.nonce = tx.nonce;
+    builder
+}
+
+#[tokio::test]
+async fn test_evm_nonce_tracker() {
+    let tracker = EvmNonceTracker::new();
+    let address = H160::repeat_byte(1);
+    assert_eq!(tracker.reserve(address, U256::from(5)).await, U256::from(5));
+    assert_eq!(tracker.reserve(address, U256::from(5)).await, U256::from(6));
+    // the failed tx gives back the latest nonce
+    tracker.release(address, U256::from(6)).await;
+    assert_eq!(tracker.next_nonce(address).await, Some(U256::from(6)));
+
+    let pending = PendingEvmTx {
+        nonce: U256::from(5),
+        hash: H256::repeat_byte(5),
+        builder: EvmTxBuilder::new(1).eip1559(U256::from(100), U256::from(1000)),
+        submitted_at: SystemTime::now(),
+        replaced: 0,
+    };
+    tracker.record(address, pending.clone()).await;
+    assert_eq!(tracker.pending(address).await, vec![pending.clone()]);
+
+    let builder = replacement(&pending, 10);
+    assert_eq!(builder.nonce, U256::from(5));
+    assert_eq!(
+        builder.fees,
+        EvmTxFees::EIP1559(U256::from(110), U256::from(1100))
+    );
+
+    // only replaced after the timeout and at most 'max_replacements' times
+    let tracker = EvmNonceTracker::with_limits(Duration::from_secs(60), 2);
+    tracker.record(address, pending.clone()).await;
+    let now = pending.submitted_at;
+    assert!(tracker.stuck(address, U256::from(5), now).await.is_empty());
+    let later = now + Duration::from_secs(61);
+    assert_eq!(tracker.stuck(address, U256::from(5), later).await.len(), 1);
+    assert!(tracker
+        .stuck(address, U256::from(6), later)
+        .await
+        .is_empty());
+    tracker
+        .record(
+            address,
+            PendingEvmTx {
+                replaced: 2,
+                ..pending.clone()
+            },
+        )
+        .await;
+    assert!(tracker
+        .stuck(address, U256::from(5), later)
+        .await
+        .is_empty());
+
+    // the chain nonce passes the pending tx
+    let tracker = EvmNonceTracker::new();
+    tracker.record(address, pending.clone()).await;
+    assert_eq!(tracker.reserve(address, U256::from(8)).await, U256::from(8));
+    assert!(tracker.pending(address).await.is_empty());
+    // other addresses are independent
+    assert_eq!(
+        tracker.reserve(H160::repeat_byte(2), U256::zero()).await,
+        U256::zero()
+    );
+}
@@ -0,0 +1,271 @@
+//! Resolve the substrate extrinsic of 'Ethereum::transact' or 'transact_unsigned' to the
+//! evm receipt, by the 'Executed' event and the 'CurrentReceipts' and 'CurrentTransactionStatuses'
+//! storage of the block including it.
+use crate::deepsafe::ethereum::events::Executed;
+use crate::deepsafe::runtime_types::ethereum::log::Log;
+use crate::deepsafe::runtime_types::ethereum::receipt::ReceiptV3;
+use crate::deepsafe::runtime_types::evm_core::error::ExitReason;
+use crate::evm::abi::{decode_logs, DecodedLog, EventAbi};
+use crate::extrinsic::extrinsic_hash;
+use crate::tx_tracker::TxStatus;
+use crate::{At, DeepSafeSubClient};
+use sp_core::{H160, H256 as Hash, U256};
+use subxt::events::Phase;
+use subxt::Error;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmReceipt {
+    pub block_hash: Hash,
+    // index of the substrate extrinsic in the block
+    pub extrinsic_index: u32,
+    pub extrinsic_hash: Hash,
+    // ethereum tx hash
+    pub transaction_hash: Hash,
+    // index of the tx in the ethereum block
+    pub transaction_index: u32,
+    pub from: H160,
+    pub to: Option<H160>,
+    pub contract_address: Option<H160>,
+    pub exit_reason: ExitReason,
+    // 'status_code' of the receipt is 1
+    pub status: bool,
+    pub gas_used: U256,
+    pub cumulative_gas_used: U256,
+    pub logs: Vec<Log>,
+}
+
+impl EvmReceipt {
+    /// Logs of the receipt emitted by any of 'abis'.
+    pub fn decoded_logs(&self, abis: &[EventAbi]) -> Result<Vec<DecodedLog>, String> {
+        decode_logs(&self.logs, abis)
+    }
+}
+
+/// Receipt of the evm tx executed by the extrinsic in the block, 'None' if the extrinsic
+/// isn't in the block or doesn't execute an evm tx.
+pub async fn receipt_by_extrinsic(
+    sub_client: &DeepSafeSubClient,
+    block_hash: Hash,
+    extrinsic_hash: Hash,
+) -> Result<Option<EvmReceipt>, Error> {
+    let block = match sub_client
+        .client
+        .read()
+        .await
+        .rpc()
+        .block(Some(block_hash))
+        .await?
+    {
+        Some(block) => block,
+        None => return Ok(None),
+    };
+    let extrinsic_index = match block
+        .block
+        .extrinsics
+        .iter()
+        .position(|extrinsic| crate::extrinsic::extrinsic_hash(&extrinsic.0) == extrinsic_hash)
+    {
+        Some(index) => index as u32,
+        None => return Ok(None),
+    };
+    let events = sub_client
+        .client
+        .read()
+        .await
+        .events()
+        .at(block_hash)
+        .await?;
+    for event in events.iter() {
+        let event = event?;
+        if event.phase() != Phase::ApplyExtrinsic(extrinsic_index) {
+            continue;
+        }
+        if let Some(executed) = event.as_event::<Executed>()? {
+            return build_receipt(
+                sub_client,
+                block_hash,
+                extrinsic_index,
+                extrinsic_hash,
+                executed,
+            )
+            .await;
+        }
+    }
+    Ok(None)
+}
+
+/// Receipt of the evm tx with the ethereum 'transaction_hash' in the block.
+pub async fn receipt_by_transaction_hash(
+    sub_client: &DeepSafeSubClient,
+    block_hash: Hash,
+    transaction_hash: Hash,
+) -> Result<Option<EvmReceipt>, Error> {
+    let block = match sub_client
+        .client
+        .read()
+        .await
+        .rpc()
+        .block(Some(block_hash))
+        .await?
+    {
+        Some(block) => block,
+        None => return Ok(None),
+    };
+    let events = sub_client
+        .client
+        .read()
+        .await
+        .events()
+        .at(block_hash)
+        .await?;
+    for event in events.iter() {
+        let event = event?;
+        let extrinsic_index = match event.phase() {
+            Phase::ApplyExtrinsic(index) => index,
+            _ => continue,
+        };
+        match event.as_event::<Executed>()? {
+            Some(executed) if executed.transaction_hash == transaction_hash => {
+                let extrinsic = block
+                    .block
+                    .extrinsics
+                    .get(extrinsic_index as usize)
+                    .ok_or_else(|| {
+                        Error::Other(format!("no extrinsic {extrinsic_index} in {block_hash:?}"))
+                    })?;
+                return build_receipt(
+                    sub_client,
+                    block_hash,
+                    extrinsic_index,
+                    extrinsic_hash(&extrinsic.0),
+                    executed,
+                )
+                .await;
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Find the receipt of the extrinsic, in the block reported by 'tx_tracker' if tracked,
+/// otherwise in 'max_blocks' blocks back from 'at_block'.
+pub async fn find_receipt(
+    sub_client: &DeepSafeSubClient,
+    extrinsic_hash: Hash,
+    at_block: At,
+    max_blocks: u32,
+) -> Result<Option<EvmReceipt>, Error> {
+    if let Some(tracker) = &sub_client.tx_tracker {
+        match tracker.status(&extrinsic_hash).await {
+            Some(TxStatus::InBlock(block_hash))
+            | Some(TxStatus::Finalized(block_hash))
+            | Some(TxStatus::FinalityTimeout(block_hash)) => {
+                return receipt_by_extrinsic(sub_client, block_hash, extrinsic_hash).await;
+            }
+            Some(status) if status.is_final() => return Ok(None),
+            _ => {}
+        }
+    }
+    let mut block_hash = match sub_client.resolve_at(at_block).await? {
+        Some(hash) => hash,
+        None => sub_client
+            .client
+            .read()
+            .await
+            .rpc()
+            .block_hash(None)
+            .await?
+            .ok_or_else(|| Error::Other("no best block hash".to_string()))?,
+    };
+    for _ in 0..max_blocks {
+        if let Some(receipt) = receipt_by_extrinsic(sub_client, block_hash, extrinsic_hash).await? {
+            return Ok(Some(receipt));
+        }
+        let header = match sub_client
+            .client
+            .read()
+            .await
+            .rpc()
+            .header(Some(block_hash))
+            .await?
+        {
+            Some(header) if header.number > 0 => header,
+            _ => break,
+        };
+        block_hash = header.parent_hash;
+    }
+    Ok(None)
+}
+
+async fn build_receipt(
+    sub_client: &DeepSafeSubClient,
+    block_hash: Hash,
+    extrinsic_index: u32,
+    extrinsic_hash: Hash,
+    executed: Executed,
+) -> Result<Option<EvmReceipt>, Error> {
+    let at_block = At::Hash(block_hash);
+    let statuses = crate::query::ethereum::current_transaction_statuses(sub_client, at_block)
+        .await?
+        .unwrap_or_default();
+    let status = match statuses
+        .into_iter()
+        .find(|status| status.transaction_hash == executed.transaction_hash)
+    {
+        Some(status) => status,
+        None => return Ok(None),
+    };
+    let receipts = crate::query::ethereum::current_receipts(sub_client, at_block)
+        .await?
+        .unwrap_or_default();
+    let index = status.transaction_index as usize;
+    let (status_code, cumulative_gas_used, logs) = match receipts.get(index) {
+        Some(receipt) => receipt_data(receipt),
+        None => {
+            return Err(Error::Other(format!(
+                "no receipt {index} of evm tx {:?}",
+                executed.transaction_hash
+            )))
+        }
+    };
+    let previous_gas_used = match index {
+        0 => U256::zero(),
+        _ => receipt_data(&receipts[index - 1]).1,
+    };
+    Ok(Some(EvmReceipt {
+        block_hash,
+        extrinsic_index,
+        extrinsic_hash,
+        transaction_hash: executed.transaction_hash,
+        transaction_index: status.transaction_index,
+        from: status.from,
+        to: status.to,
+        contract_address: status.contract_address,
+        exit_reason: executed.exit_reason,
+        status: status_code == 1,
+        gas_used: cumulative_gas_used.saturating_sub(previous_gas_used),
+        cumulative_gas_used,
+        logs,
+    }))
+}
+
+// (status_code, cumulative used_gas, logs)
+fn receipt_data(receipt: &ReceiptV3) -> (u8, U256, Vec<Log>) {
+    let data = match receipt {
+        ReceiptV3::Legacy(data) | ReceiptV3::EIP2930(data) | ReceiptV3::EIP1559(data) => data,
+    };
+    (data.status_code, U256(data.used_gas.0), data.logs.clone())
+}
+
+#[tokio::test]
+async fn test_find_evm_receipt() {
+    let url = "ws://127.0.0.1:9944".to_string();
+    let client = crate::client::SubClient::new_from_signer(&url, None, None, None)
+        .await
+        .unwrap();
+    let receipt = find_receipt(&client, Hash::zero(), At::Latest, 3)
+        .await
+        .unwrap();
+    assert!(receipt.is_none());
+}
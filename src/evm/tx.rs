@@ -0,0 +1,301 @@
+//! Build and sign Legacy, EIP-2930 and EIP-1559 evm tx, for calls and contract creation.
+use crate::deepsafe::runtime_types::ethereum::transaction::TransactionV2 as RuntimeTransaction;
+use codec::{Decode, Encode};
+use ethereum::{
+    AccessListItem, EIP1559Transaction, EIP1559TransactionMessage, EIP2930Transaction,
+    EIP2930TransactionMessage, LegacyTransaction, LegacyTransactionMessage, TransactionAction,
+    TransactionSignature, TransactionV2,
+};
+use sp_core::{H160, H256, U256};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EvmTxFees {
+    /// 'gas_price' of a legacy tx.
+    Legacy(U256),
+    /// 'gas_price' of an EIP-2930 tx.
+    EIP2930(U256),
+    /// '(max_priority_fee_per_gas, max_fee_per_gas)' of an EIP-1559 tx.
+    EIP1559(U256, U256),
+    /// EIP-1559 tx with the default fees of 'precompile'.
+    #[default]
+    Default,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmTxBuilder {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub fees: EvmTxFees,
+    pub gas_limit: U256,
+    pub action: TransactionAction,
+    pub value: U256,
+    pub input: Vec<u8>,
+    // ignored by legacy tx
+    pub access_list: Vec<AccessListItem>,
+}
+
+impl EvmTxBuilder {
+    /// EIP-1559 tx creating a contract with the default fees, nonce 0 and no gas limit.
+    pub fn new(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            nonce: U256::zero(),
+            fees: EvmTxFees::Default,
+            gas_limit: U256::zero(),
+            action: TransactionAction::Create,
+            value: U256::zero(),
+            input: vec![],
+            access_list: vec![],
+        }
+    }
+
+    pub fn legacy(mut self, gas_price: U256) -> Self {
+        self.fees = EvmTxFees::Legacy(gas_price);
+        self
+    }
+
+    pub fn eip2930(mut self, gas_price: U256) -> Self {
+        self.fees = EvmTxFees::EIP2930(gas_price);
+        self
+    }
+
+    pub fn eip1559(mut self, max_priority_fee_per_gas: U256, max_fee_per_gas: U256) -> Self {
+        self.fees = EvmTxFees::EIP1559(max_priority_fee_per_gas, max_fee_per_gas);
+        self
+    }
+
+    pub fn nonce(mut self, nonce: U256) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    pub fn gas_limit(mut self, gas_limit: U256) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Call the contract 'to' with 'input'.
+    pub fn call(mut self, to: H160, input: Vec<u8>) -> Self {
+        self.action = TransactionAction::Call(to);
+        self.input = input;
+        self
+    }
+
+    /// Create a contract with the init code.
+    pub fn create(mut self, init: Vec<u8>) -> Self {
+        self.action = TransactionAction::Create;
+        self.input = init;
+        self
+    }
+
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Add the storage keys of 'address' to the access list.
+    pub fn access(mut self, address: H160, storage_keys: Vec<H256>) -> Self {
+        self.access_list.push(AccessListItem {
+            address,
+            storage_keys,
+        });
+        self
+    }
+
+    /// Sign the tx with the secp256k1 secret key.
+    pub fn sign(&self, sk: &[u8; 32]) -> Result<TransactionV2, String> {
+        let secret = secp256k1::SecretKey::parse(sk)
+            .map_err(|e| format!("Parse evm signer sk failed for: {:?}", e))?;
+        let sign = |hash: H256| {
+            let message = secp256k1::Message::parse_slice(&hash[..]).map_err(|e| e.to_string())?;
+            let (signature, recid) = secp256k1::sign(&message, &secret);
+            let rs = signature.serialize();
+            Ok::<_, String>((
+                recid.serialize(),
+                H256::from_slice(&rs[0..32]),
+                H256::from_slice(&rs[32..64]),
+            ))
+        };
+        let tx = match self.fees {
+            EvmTxFees::Legacy(gas_price) => {
+                let message = LegacyTransactionMessage {
+                    nonce: self.nonce,
+                    gas_price,
+                    gas_limit: self.gas_limit,
+                    action: self.action,
+                    value: self.value,
+                    input: self.input.clone(),
+                    chain_id: Some(self.chain_id),
+                };
+                let (recid, r, s) = sign(message.hash())?;
+                // EIP-155 'v'
+                let v = recid as u64 + self.chain_id * 2 + 35;
+                TransactionV2::Legacy(LegacyTransaction {
+                    nonce: message.nonce,
+                    gas_price: message.gas_price,
+                    gas_limit: message.gas_limit,
+                    action: message.action,
+                    value: message.value,
+                    input: message.input,
+                    signature: TransactionSignature::new(v, r, s)
+                        .ok_or("Invalid legacy evm tx signature")?,
+                })
+            }
+            EvmTxFees::EIP2930(gas_price) => {
+                let message = EIP2930TransactionMessage {
+                    chain_id: self.chain_id,
+                    nonce: self.nonce,
+                    gas_price,
+                    gas_limit: self.gas_limit,
+                    action: self.action,
+                    value: self.value,
+                    input: self.input.clone(),
+                    access_list: self.access_list.clone(),
+                };
+                let (recid, r, s) = sign(message.hash())?;
+                TransactionV2::EIP2930(EIP2930Transaction {
+                    chain_id: message.chain_id,
+                    nonce: message.nonce,
+                    gas_price: message.gas_price,
+                    gas_limit: message.gas_limit,
+                    action: message.action,
+                    value: message.value,
+                    input: message.input,
+                    access_list: message.access_list,
+                    odd_y_parity: recid != 0,
+                    r,
+                    s,
+                })
+            }
+            EvmTxFees::EIP1559(..) | EvmTxFees::Default => {
+                let message = self.eip1559_message();
+                let (recid, r, s) = sign(message.hash())?;
+                TransactionV2::EIP1559(EIP1559Transaction {
+                    chain_id: message.chain_id,
+                    nonce: message.nonce,
+                    max_priority_fee_per_gas: message.max_priority_fee_per_gas,
+                    max_fee_per_gas: message.max_fee_per_gas,
+                    gas_limit: message.gas_limit,
+                    action: message.action,
+                    value: message.value,
+                    input: message.input,
+                    access_list: message.access_list,
+                    odd_y_parity: recid != 0,
+                    r,
+                    s,
+                })
+            }
+        };
+        Ok(tx)
+    }
+
+    fn eip1559_message(&self) -> EIP1559TransactionMessage {
+        let (max_priority_fee_per_gas, max_fee_per_gas) = match self.fees {
+            EvmTxFees::EIP1559(max_priority_fee_per_gas, max_fee_per_gas) => {
+                (max_priority_fee_per_gas, max_fee_per_gas)
+            }
+            _ => (
+                U256::from(crate::precompile::DEFAULT_MAX_PRIORITY_FEE_PER_GAS),
+                U256::from(crate::precompile::DEFAULT_MAX_FEE_PER_GAS),
+            ),
+        };
+        EIP1559TransactionMessage {
+            chain_id: self.chain_id,
+            nonce: self.nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit: self.gas_limit,
+            action: self.action,
+            value: self.value,
+            input: self.input.clone(),
+            access_list: self.access_list.clone(),
+        }
+    }
+}
+
+impl From<EIP1559TransactionMessage> for EvmTxBuilder {
+    fn from(message: EIP1559TransactionMessage) -> Self {
+        Self {
+            chain_id: message.chain_id,
+            nonce: message.nonce,
+            fees: EvmTxFees::EIP1559(message.max_priority_fee_per_gas, message.max_fee_per_gas),
+            gas_limit: message.gas_limit,
+            action: message.action,
+            value: message.value,
+            input: message.input,
+            access_list: message.access_list,
+        }
+    }
+}
+
+/// Convert to the tx type of runtime call 'Ethereum::transact', they share the same encoding.
+pub fn to_runtime_transaction(tx: &TransactionV2) -> Result<RuntimeTransaction, String> {
+    RuntimeTransaction::decode(&mut &tx.encode()[..]).map_err(|e| e.to_string())
+}
+
+/// Recover the sender address from the signature of the tx.
+pub fn recover_signer(tx: &TransactionV2) -> Result<H160, String> {
+    let (hash, recid, r, s) = match tx {
+        TransactionV2::Legacy(t) => (
+            LegacyTransactionMessage::from(t.clone()).hash(),
+            t.signature.standard_v(),
+            *t.signature.r(),
+            *t.signature.s(),
+        ),
+        TransactionV2::EIP2930(t) => (
+            EIP2930TransactionMessage::from(t.clone()).hash(),
+            t.odd_y_parity as u8,
+            t.r,
+            t.s,
+        ),
+        TransactionV2::EIP1559(t) => (
+            EIP1559TransactionMessage::from(t.clone()).hash(),
+            t.odd_y_parity as u8,
+            t.r,
+            t.s,
+        ),
+    };
+    let mut rs = [0u8; 64];
+    rs[0..32].copy_from_slice(&r[..]);
+    rs[32..64].copy_from_slice(&s[..]);
+    let message = secp256k1::Message::parse_slice(&hash[..]).map_err(|e| e.to_string())?;
+    let signature = secp256k1::Signature::parse_slice(&rs).map_err(|e| format!("{e:?}"))?;
+    let recid = secp256k1::RecoveryId::parse(recid).map_err(|e| format!("{e:?}"))?;
+    let public = secp256k1::recover(&message, &signature, &recid).map_err(|e| format!("{e:?}"))?;
+    Ok(public_key_to_address(&public))
+}
+
+/// Evm address of the secp256k1 public key.
+pub fn public_key_to_address(public: &secp256k1::PublicKey) -> H160 {
+    H160::from_slice(&sp_core::keccak_256(&public.serialize()[1..])[12..])
+}
+
+#[test]
+fn test_sign_and_recover_evm_tx() {
+    let sk = [7u8; 32];
+    let secret = secp256k1::SecretKey::parse(&sk).unwrap();
+    let signer = public_key_to_address(&secp256k1::PublicKey::from_secret_key(&secret));
+    let to = H160::from_low_u64_be(1104);
+
+    let builders = [
+        EvmTxBuilder::new(1)
+            .legacy(U256::from(10))
+            .call(to, vec![1]),
+        EvmTxBuilder::new(1)
+            .eip2930(U256::from(10))
+            .call(to, vec![2])
+            .access(to, vec![H256::repeat_byte(1)]),
+        EvmTxBuilder::new(1)
+            .eip1559(U256::from(1), U256::from(10))
+            .nonce(U256::from(3))
+            .gas_limit(U256::from(21_000))
+            .create(vec![0x60, 0x00])
+            .access(to, vec![]),
+    ];
+    for builder in builders {
+        let tx = builder.sign(&sk).unwrap();
+        assert_eq!(recover_signer(&tx).unwrap(), signer);
+        let runtime_tx = to_runtime_transaction(&tx).unwrap();
+        assert_eq!(runtime_tx.encode(), tx.encode());
+    }
+}
@@ -0,0 +1,237 @@
+//! Decode SCALE encoded extrinsic bytes into pallet/call names and json arguments,
+//! i.e. for relays to audit the '*_call_bytes' before forwarding them.
+use codec::{Compact, Decode};
+use def_node_primitives::{AccountId20, EthereumSignature};
+use scale_info::{PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use subxt::ext::scale_value::{self, Composite, Primitive, Value, ValueDef};
+use subxt::Metadata;
+
+/// The only extrinsic format version supported by the runtime.
+pub const EXTRINSIC_FORMAT_VERSION: u8 = 4;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecodedExtrinsic {
+    pub signed: bool,
+    // hex of the signer account, 'None' for unsigned tx
+    pub signer: Option<String>,
+    pub nonce: Option<u32>,
+    pub tip: Option<u128>,
+    // hex of 'blake2_256' of the whole encoded extrinsic
+    pub hash: String,
+    pub pallet: String,
+    pub call: String,
+    // call arguments by name, bytes are shown as hex
+    pub args: JsonValue,
+}
+
+/// Decode signed or unsigned extrinsic bytes, with or without the length prefix.
+pub fn decode_extrinsic(bytes: &[u8], metadata: &Metadata) -> Result<DecodedExtrinsic, String> {
+    let hash = extrinsic_hash(bytes);
+    let mut input = strip_length_prefix(bytes);
+    let version = u8::decode(&mut input).map_err(|e| e.to_string())?;
+    if version & 0b0111_1111 != EXTRINSIC_FORMAT_VERSION {
+        return Err(format!(
+            "unsupported extrinsic version {}",
+            version & 0b0111_1111
+        ));
+    }
+    let signed = version & 0b1000_0000 != 0;
+    let (mut signer, mut nonce, mut tip) = (None, None, None);
+    if signed {
+        let address = sp_runtime::MultiAddress::<AccountId20, ()>::decode(&mut input)
+            .map_err(|e| format!("decode signer failed: {e}"))?;
+        signer = match address {
+            sp_runtime::MultiAddress::Id(account) => Some(format!("0x{}", hex::encode(account.0))),
+            other => Some(format!("{other:?}")),
+        };
+        EthereumSignature::decode(&mut input)
+            .map_err(|e| format!("decode signature failed: {e}"))?;
+        for extension in metadata.extrinsic().signed_extensions() {
+            let value = scale_value::scale::decode_as_type(
+                &mut input,
+                extension.extra_ty(),
+                metadata.types(),
+            )
+            .map_err(|e| format!("decode {} failed: {e}", extension.identifier()))?;
+            match extension.identifier() {
+                "CheckNonce" => nonce = value_as_u128(&value).map(|n| n as u32),
+                "ChargeTransactionPayment" => tip = value_as_u128(&value),
+                _ => {}
+            }
+        }
+    }
+
+    let (pallet_index, call_index) = <(u8, u8)>::decode(&mut input).map_err(|e| e.to_string())?;
+    let pallet = metadata
+        .pallet_by_index(pallet_index)
+        .ok_or_else(|| format!("pallet {pallet_index} not found in metadata"))?;
+    let call = pallet.call_variant_by_index(call_index).ok_or_else(|| {
+        format!(
+            "call {call_index} of {} not found in metadata",
+            pallet.name()
+        )
+    })?;
+    let mut args = Map::new();
+    for (i, field) in call.fields.iter().enumerate() {
+        let value = scale_value::scale::decode_as_type(&mut input, field.ty.id, metadata.types())
+            .map_err(|e| format!("decode argument {i} of {} failed: {e}", call.name))?;
+        let name = field.name.clone().unwrap_or_else(|| i.to_string());
+        args.insert(name, value_to_json(&value, metadata.types()));
+    }
+    if !input.is_empty() {
+        return Err(format!("{} trailing bytes after the call", input.len()));
+    }
+
+    Ok(DecodedExtrinsic {
+        signed,
+        signer,
+        nonce,
+        tip,
+        hash: format!("0x{}", hex::encode(hash)),
+        pallet: pallet.name().to_string(),
+        call: call.name.clone(),
+        args: JsonValue::Object(args),
+    })
+}
+
+/// Hash of the extrinsic as in the tx pool, 'bytes' is with or without the length prefix.
+pub fn extrinsic_hash(bytes: &[u8]) -> sp_core::H256 {
+    sp_core::blake2_256(&encode_with_length_prefix(strip_length_prefix(bytes))).into()
+}
+
+// the encoded extrinsic is 'Vec<u8>' encoded, the prefix is stripped only if it matches the length
+fn strip_length_prefix(bytes: &[u8]) -> &[u8] {
+    let mut input = bytes;
+    match Compact::<u32>::decode(&mut input) {
+        Ok(len) if len.0 as usize == input.len() => input,
+        _ => bytes,
+    }
+}
+
+fn encode_with_length_prefix(bytes: &[u8]) -> Vec<u8> {
+    codec::Encode::encode(&bytes)
+}
+
+fn value_as_u128(value: &Value<u32>) -> Option<u128> {
+    match &value.value {
+        ValueDef::Primitive(Primitive::U128(n)) => Some(*n),
+        ValueDef::Composite(composite) => {
+            let values = composite.values().collect::<Vec<_>>();
+            match values.as_slice() {
+                [value] => value_as_u128(value),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_u8(types: &PortableRegistry, ty: u32) -> bool {
+    matches!(
+        types.resolve(ty).map(|ty| &ty.type_def),
+        Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+    )
+}
+
+// 'Vec<u8>' or '[u8; N]'
+fn is_bytes(types: &PortableRegistry, ty: u32) -> bool {
+    match types.resolve(ty).map(|ty| &ty.type_def) {
+        Some(TypeDef::Sequence(seq)) => is_u8(types, seq.type_param.id),
+        Some(TypeDef::Array(array)) => is_u8(types, array.type_param.id),
+        _ => false,
+    }
+}
+
+/// Json of the decoded value, byte sequences are shown as hex and composites with a single
+/// unnamed field (i.e. 'AccountId20([u8; 20])') are shown as the inner value.
+pub fn value_to_json(value: &Value<u32>, types: &PortableRegistry) -> JsonValue {
+    match &value.value {
+        ValueDef::Composite(composite) if is_bytes(types, value.context) => {
+            let bytes = composite
+                .values()
+                .filter_map(value_as_u128)
+                .map(|b| b as u8)
+                .collect::<Vec<_>>();
+            JsonValue::String(format!("0x{}", hex::encode(bytes)))
+        }
+        ValueDef::Composite(composite) => composite_to_json(composite, types),
+        ValueDef::Variant(variant) => {
+            if variant.values.is_empty() {
+                JsonValue::String(variant.name.clone())
+            } else {
+                let mut map = Map::new();
+                map.insert(
+                    variant.name.clone(),
+                    composite_to_json(&variant.values, types),
+                );
+                JsonValue::Object(map)
+            }
+        }
+        ValueDef::BitSequence(bits) => JsonValue::Array(bits.iter().map(JsonValue::Bool).collect()),
+        ValueDef::Primitive(primitive) => match primitive {
+            Primitive::Bool(b) => JsonValue::Bool(*b),
+            Primitive::Char(c) => JsonValue::String(c.to_string()),
+            Primitive::String(s) => JsonValue::String(s.clone()),
+            // keep big numbers as string to avoid precision loss in json
+            Primitive::U128(n) => match u64::try_from(*n) {
+                Ok(n) => JsonValue::from(n),
+                Err(_) => JsonValue::String(n.to_string()),
+            },
+            Primitive::I128(n) => match i64::try_from(*n) {
+                Ok(n) => JsonValue::from(n),
+                Err(_) => JsonValue::String(n.to_string()),
+            },
+            Primitive::U256(n) | Primitive::I256(n) => {
+                JsonValue::String(format!("0x{}", hex::encode(n)))
+            }
+        },
+    }
+}
+
+fn composite_to_json(composite: &Composite<u32>, types: &PortableRegistry) -> JsonValue {
+    match composite {
+        Composite::Named(fields) => JsonValue::Object(
+            fields
+                .iter()
+                .map(|(name, value)| (name.clone(), value_to_json(value, types)))
+                .collect(),
+        ),
+        Composite::Unnamed(values) => match values.as_slice() {
+            [value] => value_to_json(value, types),
+            _ => JsonValue::Array(values.iter().map(|v| value_to_json(v, types)).collect()),
+        },
+    }
+}
+
+#[test]
+fn test_decode_unsigned_extrinsic() {
+    use crate::compatibility::COMPILED_METADATA;
+    use subxt::tx::TxPayload;
+
+    let metadata = Metadata::decode(&mut &COMPILED_METADATA[..]).unwrap();
+    let call = crate::deepsafe::tx()
+        .committee()
+        .enter_epoch(5, vec![(vec![1, 2], vec![3], vec![])]);
+    let mut inner = vec![EXTRINSIC_FORMAT_VERSION];
+    call.encode_call_data_to(&metadata, &mut inner).unwrap();
+    let encoded = encode_with_length_prefix(&inner);
+
+    for bytes in [&encoded[..], &inner[..]] {
+        let decoded = decode_extrinsic(bytes, &metadata).unwrap();
+        assert!(!decoded.signed);
+        assert_eq!(decoded.signer, None);
+        assert_eq!(decoded.pallet, "Committee");
+        assert_eq!(decoded.call, "enter_epoch");
+        assert_eq!(decoded.args["epoch"], JsonValue::from(5u64));
+        assert_eq!(
+            decoded.args["proofs"],
+            serde_json::json!([["0x0102", "0x03", "0x"]])
+        );
+        assert_eq!(
+            decoded.hash,
+            format!("0x{}", hex::encode(sp_core::blake2_256(&encoded)))
+        );
+    }
+}
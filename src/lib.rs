@@ -1,13 +1,22 @@
 #![deny(unused_crate_dependencies)]
+pub mod cache;
+pub mod channel;
 pub mod client;
+pub mod compatibility;
 pub mod event_watcher;
+pub mod evm;
+pub mod extrinsic;
 pub mod monitor_rpc;
+pub mod offline;
+pub mod precompile;
 pub mod query;
+pub mod storage;
 pub mod submit;
+pub mod tx_tracker;
 pub mod types;
 pub mod watcher_rpc;
 
-pub use crate::client::DeepSafeConfig;
+pub use crate::client::{At, DeepSafeConfig, SubmitOptions, WaitFor};
 pub use def_node_primitives;
 use def_node_primitives::CustomError;
 pub use subxt::constants::Address;
@@ -199,6 +208,90 @@ impl CommitteeAssetsEvent {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum StakingEvent {
+    EraPayout,
+    Reward,
+    Slashed,
+    SlashReported,
+    OldSlashingReportDiscarded,
+    StakersElected,
+    Bonded,
+    Unbonded,
+    Withdrawn,
+    Kicked,
+    StakingElectionFailed,
+    Chill,
+    PayoutStarted,
+    Validator,
+    ForceEra,
+    ChangePayee,
+    BondExtra,
+    ReBond,
+    Nominator,
+    SnapshotVotersSizeExceeded,
+    SnapshotTargetsSizeExceeded,
+    EraRewardInfo,
+    EraRewardInfoEx,
+    Unknown,
+}
+
+impl StakingEvent {
+    pub fn event_names() -> Vec<String> {
+        vec![
+            "EraPayout".into(),
+            "Reward".into(),
+            "Slashed".into(),
+            "SlashReported".into(),
+            "OldSlashingReportDiscarded".into(),
+            "StakersElected".into(),
+            "Bonded".into(),
+            "Unbonded".into(),
+            "Withdrawn".into(),
+            "Kicked".into(),
+            "StakingElectionFailed".into(),
+            "Chill".into(),
+            "PayoutStarted".into(),
+            "Validator".into(),
+            "ForceEra".into(),
+            "ChangePayee".into(),
+            "BondExtra".into(),
+            "ReBond".into(),
+            "Nominator".into(),
+            "SnapshotVotersSizeExceeded".into(),
+            "SnapshotTargetsSizeExceeded".into(),
+            "EraRewardInfo".into(),
+            "EraRewardInfoEx".into(),
+        ]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SessionEvent {
+    NewSession,
+    Unknown,
+}
+
+impl SessionEvent {
+    pub fn event_names() -> Vec<String> {
+        vec!["NewSession".into()]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GrandpaEvent {
+    NewAuthorities,
+    Paused,
+    Resumed,
+    Unknown,
+}
+
+impl GrandpaEvent {
+    pub fn event_names() -> Vec<String> {
+        vec!["NewAuthorities".into(), "Paused".into(), "Resumed".into()]
+    }
+}
+
 impl std::str::FromStr for CommitteeEvent {
     type Err = ();
     fn from_str(input: &str) -> Result<CommitteeEvent, Self::Err> {
@@ -306,6 +399,60 @@ impl std::str::FromStr for RpcEvent {
     }
 }
 
+impl std::str::FromStr for StakingEvent {
+    type Err = ();
+    fn from_str(input: &str) -> Result<StakingEvent, Self::Err> {
+        match input {
+            "EraPayout" => Ok(StakingEvent::EraPayout),
+            "Reward" => Ok(StakingEvent::Reward),
+            "Slashed" => Ok(StakingEvent::Slashed),
+            "SlashReported" => Ok(StakingEvent::SlashReported),
+            "OldSlashingReportDiscarded" => Ok(StakingEvent::OldSlashingReportDiscarded),
+            "StakersElected" => Ok(StakingEvent::StakersElected),
+            "Bonded" => Ok(StakingEvent::Bonded),
+            "Unbonded" => Ok(StakingEvent::Unbonded),
+            "Withdrawn" => Ok(StakingEvent::Withdrawn),
+            "Kicked" => Ok(StakingEvent::Kicked),
+            "StakingElectionFailed" => Ok(StakingEvent::StakingElectionFailed),
+            "Chill" => Ok(StakingEvent::Chill),
+            "PayoutStarted" => Ok(StakingEvent::PayoutStarted),
+            "Validator" => Ok(StakingEvent::Validator),
+            "ForceEra" => Ok(StakingEvent::ForceEra),
+            "ChangePayee" => Ok(StakingEvent::ChangePayee),
+            "BondExtra" => Ok(StakingEvent::BondExtra),
+            "ReBond" => Ok(StakingEvent::ReBond),
+            "Nominator" => Ok(StakingEvent::Nominator),
+            "SnapshotVotersSizeExceeded" => Ok(StakingEvent::SnapshotVotersSizeExceeded),
+            "SnapshotTargetsSizeExceeded" => Ok(StakingEvent::SnapshotTargetsSizeExceeded),
+            "EraRewardInfo" => Ok(StakingEvent::EraRewardInfo),
+            "EraRewardInfoEx" => Ok(StakingEvent::EraRewardInfoEx),
+            _ => Ok(StakingEvent::Unknown),
+        }
+    }
+}
+
+impl std::str::FromStr for SessionEvent {
+    type Err = ();
+    fn from_str(input: &str) -> Result<SessionEvent, Self::Err> {
+        match input {
+            "NewSession" => Ok(SessionEvent::NewSession),
+            _ => Ok(SessionEvent::Unknown),
+        }
+    }
+}
+
+impl std::str::FromStr for GrandpaEvent {
+    type Err = ();
+    fn from_str(input: &str) -> Result<GrandpaEvent, Self::Err> {
+        match input {
+            "NewAuthorities" => Ok(GrandpaEvent::NewAuthorities),
+            "Paused" => Ok(GrandpaEvent::Paused),
+            "Resumed" => Ok(GrandpaEvent::Resumed),
+            _ => Ok(GrandpaEvent::Unknown),
+        }
+    }
+}
+
 pub(crate) fn convert_to_custom_error(custom: u8) -> String {
     let err = CustomError::from_num(custom);
     err.to_string()
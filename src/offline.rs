@@ -0,0 +1,125 @@
+//! Build and sign extrinsics without a node, i.e. on an air-gapped machine with a cold key.
+//!
+//! The metadata, runtime version and genesis hash are exported by an online 'SubClient' with
+//! 'offline_client_params', and the signed bytes are submitted later by
+//! 'submit_extrinsic_without_signer_from_bytes'.
+use crate::DeepSafeConfig;
+use codec::Decode;
+use def_node_primitives::AccountId20;
+use serde::{Deserialize, Serialize};
+use sp_core::H256 as Hash;
+use subxt::config::extrinsic_params::{BaseExtrinsicParamsBuilder, Era};
+use subxt::config::polkadot::PolkadotExtrinsicParamsBuilder;
+use subxt::rpc::types::RuntimeVersion;
+use subxt::tx::{DeepSafeSigner, Signer, TxPayload};
+use subxt::{Error, Metadata, OfflineClient};
+
+/// Chain params the signed extrinsic commits to, besides the metadata.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineParams {
+    pub genesis_hash: Hash,
+    pub spec_version: u32,
+    pub transaction_version: u32,
+}
+
+/// Extrinsic params of an offline tx, the mortal era needs a known block as checkpoint.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OfflineTxOptions {
+    pub tip: u128,
+    // (period, number of the checkpoint block, hash of the checkpoint block)
+    pub mortality: Option<(u64, u64, Hash)>,
+}
+
+pub struct OfflineSubClient {
+    pub client: OfflineClient<DeepSafeConfig>,
+    signer: DeepSafeSigner<DeepSafeConfig>,
+}
+
+impl OfflineSubClient {
+    /// Offline client from the encoded metadata, i.e. the bytes of 'metadata.scale'.
+    pub fn new(
+        metadata: &[u8],
+        params: OfflineParams,
+        signer: DeepSafeSigner<DeepSafeConfig>,
+    ) -> Result<Self, Error> {
+        let metadata = Metadata::decode(&mut &metadata[..])?;
+        let runtime_version = RuntimeVersion {
+            spec_version: params.spec_version,
+            transaction_version: params.transaction_version,
+            other: Default::default(),
+        };
+        Ok(Self {
+            client: OfflineClient::new(params.genesis_hash, runtime_version, metadata),
+            signer,
+        })
+    }
+
+    /// Offline client with the compiled 'metadata.scale', which must match the runtime.
+    pub fn from_compiled_metadata(
+        params: OfflineParams,
+        signer: DeepSafeSigner<DeepSafeConfig>,
+    ) -> Result<Self, Error> {
+        Self::new(crate::compatibility::COMPILED_METADATA, params, signer)
+    }
+
+    pub fn account_id(&self) -> AccountId20 {
+        self.signer.account_id().clone()
+    }
+
+    /// Sign the call with the explicit 'nonce' and return the encoded extrinsic with the
+    /// length prefix, same as 'SubClient::signed_tx_encode_to_bytes'.
+    pub fn signed_tx_encode_to_bytes<Call: TxPayload>(
+        &self,
+        call: Call,
+        nonce: u32,
+        options: &OfflineTxOptions,
+    ) -> Result<Vec<u8>, Error> {
+        self.client.tx().validate(&call)?;
+        let tx = self.client.tx().create_signed_with_nonce(
+            &call,
+            &self.signer,
+            nonce,
+            extrinsic_params(options),
+        )?;
+        Ok(tx.into_encoded())
+    }
+}
+
+fn extrinsic_params(options: &OfflineTxOptions) -> PolkadotExtrinsicParamsBuilder<DeepSafeConfig> {
+    let params = BaseExtrinsicParamsBuilder::new().tip(options.tip);
+    match options.mortality {
+        Some((period, number, hash)) => params.era(Era::mortal(period, number), hash),
+        None => params,
+    }
+}
+
+#[test]
+fn test_offline_signed_tx() {
+    use crate::deepsafe::runtime_types::pallet_committee::types::CryptoType;
+
+    let sk =
+        hex::decode("5fb92d6e98884f76de468fa3f6278f8807c48bebc13595d45af5bdc4da702133").unwrap(); // alice
+    let signer = DeepSafeSigner::new(crate::SecretKey::parse_slice(&sk).unwrap());
+    let params = OfflineParams {
+        genesis_hash: Hash::repeat_byte(1),
+        spec_version: 100,
+        transaction_version: 1,
+    };
+    let client = OfflineSubClient::from_compiled_metadata(params, signer).unwrap();
+    let call = crate::deepsafe::tx()
+        .committee()
+        .create_committee(2, 3, CryptoType::Ecdsa, 0);
+    let options = OfflineTxOptions {
+        tip: 10,
+        mortality: Some((64, 100, Hash::repeat_byte(2))),
+    };
+    let bytes = client.signed_tx_encode_to_bytes(call, 5, &options).unwrap();
+
+    let metadata = Metadata::decode(&mut &crate::compatibility::COMPILED_METADATA[..]).unwrap();
+    let decoded = crate::extrinsic::decode_extrinsic(&bytes, &metadata).unwrap();
+    assert!(decoded.signed);
+    assert_eq!(decoded.nonce, Some(5));
+    assert_eq!(decoded.tip, Some(10));
+    assert_eq!(decoded.pallet, "Committee");
+    assert_eq!(decoded.call, "create_committee");
+}
@@ -0,0 +1,191 @@
+//! Registry of DeepSafe EVM precompile methods called by this crate.
+//!
+//! A new method is added by declaring a 'PrecompileCall' with its solidity signature,
+//! selector, gas limit and argument encoder, and listing it in 'METHODS' so that the
+//! selector is checked against 'keccak_256(signature)' by tests.
+//!
+//! Only the precompiles called by this crate are declared, i.e. Channel and Mining. Others
+//! like Committee are declared here together with their first caller, the address and abi of
+//! the runtime must be taken from the node rather than guessed.
+use precompile_utils::prelude::UnboundedBytes;
+use precompile_utils::solidity::codec::Writer as EvmDataWriter;
+use sp_core::{H160, H256, U256};
+
+pub const DEFAULT_MAX_PRIORITY_FEE_PER_GAS: u128 = 1_500_000_000;
+pub const DEFAULT_MAX_FEE_PER_GAS: u128 = 4_500_000_000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Precompile {
+    pub name: &'static str,
+    // the precompile address is 'H160::from_low_u64_be(index)'
+    pub index: u64,
+}
+
+impl Precompile {
+    pub fn address(&self) -> H160 {
+        H160::from_low_u64_be(self.index)
+    }
+}
+
+pub const MINING: Precompile = Precompile {
+    name: "Mining",
+    index: 1101,
+};
+
+pub const CHANNEL: Precompile = Precompile {
+    name: "Channel",
+    index: 1104,
+};
+
+/// Declaration of one precompile method.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PrecompileMethod {
+    pub precompile: Precompile,
+    pub signature: &'static str,
+    // 'keccak_256(signature)[..4]'
+    pub selector: [u8; 4],
+    pub gas_limit: u64,
+}
+
+/// A precompile method with the typed encoder of its arguments.
+#[derive(Copy, Clone, Debug)]
+pub struct PrecompileCall<Args> {
+    pub method: PrecompileMethod,
+    encode: fn(EvmDataWriter, Args) -> EvmDataWriter,
+}
+
+impl<Args> PrecompileCall<Args> {
+    pub fn address(&self) -> H160 {
+        self.method.precompile.address()
+    }
+
+    /// Evm tx input, the selector followed by the abi encoded 'args'.
+    pub fn input(&self, args: Args) -> Vec<u8> {
+        let writer = EvmDataWriter::new_with_selector(u32::from_be_bytes(self.method.selector));
+        (self.encode)(writer, args).build()
+    }
+
+    /// EIP-1559 message calling the method with the default fees.
+    pub fn tx_message(
+        &self,
+        chain_id: u64,
+        nonce: U256,
+        args: Args,
+    ) -> ethereum::EIP1559TransactionMessage {
+        ethereum::EIP1559TransactionMessage {
+            chain_id,
+            nonce,
+            max_priority_fee_per_gas: U256::from(DEFAULT_MAX_PRIORITY_FEE_PER_GAS),
+            max_fee_per_gas: U256::from(DEFAULT_MAX_FEE_PER_GAS),
+            gas_limit: U256::from(self.method.gas_limit),
+            action: ethereum::TransactionAction::Call(self.address()),
+            value: U256::zero(),
+            input: self.input(args),
+            access_list: Default::default(),
+        }
+    }
+}
+
+/// '(pk, sig, cid, fork_id, hash, signature)'
+pub const SUBMIT_TX_SIGN_RESULT: PrecompileCall<(Vec<u8>, Vec<u8>, u32, u8, H256, Vec<u8>)> =
+    PrecompileCall {
+        method: PrecompileMethod {
+            precompile: CHANNEL,
+            signature: "submitTxSignResult(bytes,bytes,uint256,uint256,bytes32,bytes)",
+            selector: [118, 72, 134, 178],
+            gas_limit: 50_000_000,
+        },
+        encode: |writer, (pk, sig, cid, fork_id, hash, signature)| {
+            writer
+                .write(UnboundedBytes::from(pk))
+                .write(UnboundedBytes::from(sig))
+                .write(cid)
+                .write(fork_id)
+                .write(hash)
+                .write(UnboundedBytes::from(signature))
+        },
+    };
+
+/// '(channel_id, cid, msg, chain_type, uid, from, to, amount)'
+pub const IMPORT_NEW_TX: PrecompileCall<(u32, u32, Vec<u8>, u16, Vec<u8>, Vec<u8>, Vec<u8>, U256)> =
+    PrecompileCall {
+        method: PrecompileMethod {
+            precompile: CHANNEL,
+            signature: "importNewTx(uint256,uint256,bytes,uint256,bytes,bytes,bytes,uint256)",
+            selector: [58, 164, 61, 2],
+            gas_limit: 500_000,
+        },
+        encode: |writer, (channel_id, cid, msg, chain_type, uid, from, to, amount)| {
+            writer
+                .write(channel_id)
+                .write(cid)
+                .write(UnboundedBytes::from(msg))
+                .write(chain_type)
+                .write(UnboundedBytes::from(uid))
+                .write(UnboundedBytes::from(from))
+                .write(UnboundedBytes::from(to))
+                .write(amount)
+        },
+    };
+
+/// '(id, purpose, msg, signature)'
+pub const JOIN_OR_EXIT_SERVICE_UNSIGNED: PrecompileCall<(Vec<u8>, u8, Vec<u8>, Vec<u8>)> =
+    PrecompileCall {
+        method: PrecompileMethod {
+            precompile: MINING,
+            signature: "joinOrExitServiceUnsigned(bytes,uint256,bytes,bytes)",
+            selector: [99, 254, 70, 76],
+            gas_limit: 50_000_000,
+        },
+        encode: |writer, (id, purpose, msg, signature)| {
+            writer
+                .write(UnboundedBytes::from(id))
+                .write(purpose)
+                .write(UnboundedBytes::from(msg))
+                .write(UnboundedBytes::from(signature))
+        },
+    };
+
+/// All declared methods.
+pub const METHODS: &[PrecompileMethod] = &[
+    SUBMIT_TX_SIGN_RESULT.method,
+    IMPORT_NEW_TX.method,
+    JOIN_OR_EXIT_SERVICE_UNSIGNED.method,
+];
+
+/// Find the declared method by the first 4 bytes of an evm tx input and the called address.
+pub fn method_by_input(address: H160, input: &[u8]) -> Option<&'static PrecompileMethod> {
+    let selector = input.get(..4)?;
+    METHODS
+        .iter()
+        .find(|m| m.precompile.address() == address && m.selector == selector)
+}
+
+#[test]
+fn test_selectors_match_signatures() {
+    for method in METHODS {
+        assert_eq!(
+            method.selector,
+            sp_core::keccak_256(method.signature.as_bytes())[..4],
+            "selector of {}",
+            method.signature
+        );
+    }
+    // selectors are unique per precompile
+    for (i, a) in METHODS.iter().enumerate() {
+        for b in &METHODS[i + 1..] {
+            assert!(a.precompile != b.precompile || a.selector != b.selector);
+        }
+    }
+}
+
+#[test]
+fn test_precompile_call_input() {
+    let input = JOIN_OR_EXIT_SERVICE_UNSIGNED.input((vec![1], 2, vec![3], vec![4]));
+    assert_eq!(input[..4], JOIN_OR_EXIT_SERVICE_UNSIGNED.method.selector);
+    assert_eq!(
+        method_by_input(MINING.address(), &input),
+        Some(&JOIN_OR_EXIT_SERVICE_UNSIGNED.method)
+    );
+    assert_eq!(method_by_input(CHANNEL.address(), &input), None);
+}
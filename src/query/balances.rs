@@ -0,0 +1,144 @@
+use crate::deepsafe::runtime_types::fp_account::AccountId20;
+use crate::deepsafe::runtime_types::frame_system::AccountInfo;
+use crate::deepsafe::runtime_types::pallet_balances::types::{
+    AccountData, BalanceLock, ReserveData,
+};
+use crate::{At, DeepSafeSubClient};
+
+/// Whether the account may be reaped by spending, same as 'Preservation' of 'frame_support'.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Preservation {
+    /// The account may be reaped, i.e. a transfer with 'AllowDeath'.
+    Expendable,
+    /// The existential deposit is kept, i.e. a transfer with 'KeepAlive'.
+    Preserve,
+}
+
+pub async fn account_info(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    at_block: At,
+) -> Result<AccountInfo<u32, AccountData<u128>>, subxt::Error> {
+    let store = crate::deepsafe::storage().system().account(account);
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+/// Free, reserved and frozen balance of the account.
+pub async fn account_data(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    at_block: At,
+) -> Result<AccountData<u128>, subxt::Error> {
+    account_info(sub_client, account, at_block)
+        .await
+        .map(|info| info.data)
+}
+
+pub async fn free_balance(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    at_block: At,
+) -> Result<u128, subxt::Error> {
+    account_data(sub_client, account, at_block)
+        .await
+        .map(|data| data.free)
+}
+
+/// Balance could be spent by the account, i.e. to pay tx fee or to transfer with 'KeepAlive'
+/// by 'Preserve', the fee is withdrawn in keep-alive mode.
+pub async fn transferable_balance(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    preservation: Preservation,
+    at_block: At,
+) -> Result<u128, subxt::Error> {
+    let existential_deposit = match preservation {
+        Preservation::Expendable => 0,
+        Preservation::Preserve => existential_deposit(sub_client).await?,
+    };
+    account_data(sub_client, account, at_block)
+        .await
+        .map(|data| transferable(&data, preservation, existential_deposit))
+}
+
+pub async fn existential_deposit(sub_client: &DeepSafeSubClient) -> Result<u128, subxt::Error> {
+    sub_client
+        .query_constant(
+            crate::deepsafe::constants()
+                .balances()
+                .existential_deposit(),
+        )
+        .await
+}
+
+pub async fn locks(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    at_block: At,
+) -> Result<Vec<BalanceLock<u128>>, subxt::Error> {
+    let store = crate::deepsafe::storage().balances().locks(account);
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn reserves(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    at_block: At,
+) -> Result<Vec<ReserveData<[u8; 8], u128>>, subxt::Error> {
+    let store = crate::deepsafe::storage().balances().reserves(account);
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn total_issuance(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<u128, subxt::Error> {
+    let store = crate::deepsafe::storage().balances().total_issuance();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+/// Same as the reducible balance of 'pallet_balances', the frozen part is allowed to overlap with
+/// the reserved part, so only the frozen balance exceeding the reserved is untouchable in 'free',
+/// and at least 'existential_deposit' is untouchable with 'Preserve'.
+pub fn transferable(
+    data: &AccountData<u128>,
+    preservation: Preservation,
+    existential_deposit: u128,
+) -> u128 {
+    let untouchable = data.frozen.saturating_sub(data.reserved);
+    let untouchable = match preservation {
+        Preservation::Expendable => untouchable,
+        Preservation::Preserve => untouchable.max(existential_deposit),
+    };
+    data.free.saturating_sub(untouchable)
+}
+
+#[test]
+fn test_transferable() {
+    use crate::deepsafe::runtime_types::pallet_balances::types::ExtraFlags;
+
+    let data = AccountData {
+        free: 1000,
+        reserved: 100,
+        frozen: 150,
+        flags: ExtraFlags(0),
+    };
+    assert_eq!(transferable(&data, Preservation::Expendable, 10), 950);
+    assert_eq!(transferable(&data, Preservation::Preserve, 10), 950);
+    assert_eq!(transferable(&data, Preservation::Preserve, 200), 800);
+
+    let data = AccountData {
+        free: 1000,
+        reserved: 0,
+        frozen: 0,
+        flags: ExtraFlags(0),
+    };
+    assert_eq!(transferable(&data, Preservation::Expendable, 10), 1000);
+    assert_eq!(transferable(&data, Preservation::Preserve, 10), 990);
+}
@@ -4,23 +4,34 @@ use crate::deepsafe::runtime_types::pallet_channel::types::{
     MergeUtxoRecord, RefreshRecord, SourceTXInfo, TaprootPair, TxMessage, UidRecord, XudtInfo,
     XudtIssueRecord,
 };
-use crate::DeepSafeSubClient;
+use crate::storage::{proof::StorageProofBundle, StorageDiff};
+use crate::{At, DeepSafeSubClient};
 use sp_core::H256 as Hash;
 
 pub async fn tx_messages(
     sub_client: &DeepSafeSubClient,
     cid: u32,
     hash: Hash,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<TxMessage<u32>>, subxt::Error> {
     let store = crate::deepsafe::storage().channel().tx_messages(cid, hash);
     sub_client.query_storage(store, at_block).await
 }
 
+pub async fn tx_messages_with_proof(
+    sub_client: &DeepSafeSubClient,
+    cid: u32,
+    hash: Hash,
+    at_block: At,
+) -> Result<(Option<TxMessage<u32>>, StorageProofBundle), subxt::Error> {
+    let store = crate::deepsafe::storage().channel().tx_messages(cid, hash);
+    sub_client.query_storage_with_proof(store, at_block).await
+}
+
 pub async fn channel_info(
     sub_client: &DeepSafeSubClient,
     channel_id: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Channel<AccountId20>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -31,7 +42,7 @@ pub async fn channel_info(
 pub async fn hashes_for_cid(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<(Vec<SourceTXInfo>, BtcTxTunnel)>, subxt::Error> {
     let store = crate::deepsafe::storage().channel().hashes_for_cid(cid);
     sub_client.query_storage(store, at_block).await
@@ -41,7 +52,7 @@ pub async fn source_tx_package(
     sub_client: &DeepSafeSubClient,
     cid: u32,
     package_key: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<SourceTXInfo>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -53,7 +64,7 @@ pub async fn source_hash_to_package_key(
     sub_client: &DeepSafeSubClient,
     chain_id: u32,
     src_hash: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<u8>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -64,7 +75,7 @@ pub async fn source_hash_to_package_key(
 pub async fn btc_committee_type(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<BtcCmtType>, subxt::Error> {
     let store = crate::deepsafe::storage().channel().btc_committee_type(cid);
     sub_client.query_storage(store, at_block).await
@@ -73,7 +84,7 @@ pub async fn btc_committee_type(
 pub async fn btc_committee_type_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<BtcCmtType>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -87,7 +98,7 @@ pub async fn btc_committee_type_iter(
 pub async fn escape_taproot(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<TaprootPair>, subxt::Error> {
     let store = crate::deepsafe::storage().channel().escape_taproots(cid);
     sub_client.query_storage(store, at_block).await
@@ -96,7 +107,7 @@ pub async fn escape_taproot(
 pub async fn escape_taproot_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<TaprootPair>, subxt::Error> {
     let store = crate::deepsafe::storage().channel().escape_taproots_root();
     sub_client
@@ -108,7 +119,7 @@ pub async fn escape_taproot_iter(
 pub async fn bound_script(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<BtcScriptPair>, subxt::Error> {
     let store = crate::deepsafe::storage().channel().bound_scripts(cid);
     sub_client.query_storage(store, at_block).await
@@ -117,7 +128,7 @@ pub async fn bound_script(
 pub async fn bound_script_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<BtcScriptPair>, subxt::Error> {
     let store = crate::deepsafe::storage().channel().bound_scripts_root();
     sub_client
@@ -130,7 +141,7 @@ pub async fn refresh_record(
     sub_client: &DeepSafeSubClient,
     inscription_hash: Vec<u8>,
     inscription_pos: u8,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<RefreshRecord>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -142,7 +153,7 @@ pub async fn merge_record(
     sub_client: &DeepSafeSubClient,
     cid: u32,
     record_hash: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Option<MergeUtxoRecord> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -164,7 +175,7 @@ pub async fn merge_record(
 pub async fn committee_xudt_list(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<XudtInfo>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -176,7 +187,7 @@ pub async fn committee_xudt_record(
     sub_client: &DeepSafeSubClient,
     cid: u32,
     args_of_token: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<XudtIssueRecord>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -188,7 +199,7 @@ pub async fn uid_consensus_record(
     sub_client: &DeepSafeSubClient,
     cid: u32,
     uid: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<UidRecord<u32>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -199,7 +210,7 @@ pub async fn uid_consensus_record(
 pub async fn committee_fee_data(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<CommitteeFeeConfig>, subxt::Error> {
     let store = crate::deepsafe::storage().channel().committee_fee_data(cid);
     sub_client.query_storage(store, at_block).await
@@ -208,7 +219,7 @@ pub async fn committee_fee_data(
 pub async fn committee_fee_data_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<(u32, CommitteeFeeConfig)>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -227,10 +238,21 @@ pub async fn committee_fee_data_iter(
         })
 }
 
+pub async fn committee_fee_data_diff(
+    sub_client: &DeepSafeSubClient,
+    from: At,
+    to: At,
+) -> Result<StorageDiff<CommitteeFeeConfig>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .channel()
+        .committee_fee_data_root();
+    sub_client.storage_diff(store, from, to).await
+}
+
 pub async fn channel_mapping_tick_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<(u32, Vec<(Vec<u8>, Vec<u8>)>)>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -252,7 +274,7 @@ pub async fn channel_mapping_tick_iter(
 pub async fn channel_mapping_tick(
     sub_client: &DeepSafeSubClient,
     channel_id: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<(Vec<u8>, Vec<u8>)>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -263,7 +285,7 @@ pub async fn channel_mapping_tick(
 pub async fn forced_withdrawal_record(
     sub_client: &DeepSafeSubClient,
     nonce_key: u128,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Option<ForcedWithdrawalRecord> {
     let store = crate::deepsafe::storage()
         .channel()
@@ -281,3 +303,14 @@ pub async fn forced_withdrawal_record(
         }
     }
 }
+
+pub async fn forced_withdrawal_record_with_proof(
+    sub_client: &DeepSafeSubClient,
+    nonce_key: u128,
+    at_block: At,
+) -> Result<(Option<ForcedWithdrawalRecord>, StorageProofBundle), subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .channel()
+        .forced_withdrawal_data(nonce_key);
+    sub_client.query_storage_with_proof(store, at_block).await
+}
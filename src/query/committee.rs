@@ -1,19 +1,30 @@
 use crate::deepsafe::runtime_types::fp_account::AccountId20;
 use crate::deepsafe::runtime_types::pallet_committee::types::{Committee, GlobalConfig};
-use crate::DeepSafeSubClient;
+use crate::storage::StorageDiff;
+use crate::{At, DeepSafeSubClient};
 use sp_core::H256 as Hash;
+use tokio::sync::mpsc::Receiver;
 
 pub async fn global_epoch(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u64, subxt::Error> {
     let store = crate::deepsafe::storage().committee().global_epoch();
     sub_client.query_storage_or_default(store, at_block).await
 }
 
+/// Receive '(block_hash, 0, epoch)' whenever the global epoch changes.
+pub fn subscribe_global_epoch(
+    sub_client: &DeepSafeSubClient,
+    buffer: usize,
+) -> Receiver<(Hash, usize, Option<u64>)> {
+    let store = crate::deepsafe::storage().committee().global_epoch();
+    sub_client.subscribe_storage(vec![store], buffer)
+}
+
 pub async fn epoch_config(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<GlobalConfig<u32>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().epoch_config();
     sub_client.query_storage_or_default(store, at_block).await
@@ -21,7 +32,7 @@ pub async fn epoch_config(
 
 pub async fn next_epoch_config(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<GlobalConfig<u32>>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().next_epoch_config();
     sub_client.query_storage(store, at_block).await
@@ -30,16 +41,28 @@ pub async fn next_epoch_config(
 pub async fn committees(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Committee<AccountId20, u32>>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().committees(cid);
     sub_client.query_storage(store, at_block).await
 }
 
+pub async fn committees_batch(
+    sub_client: &DeepSafeSubClient,
+    cids: Vec<u32>,
+    at_block: At,
+) -> Result<Vec<Option<Committee<AccountId20, u32>>>, subxt::Error> {
+    let stores = cids
+        .into_iter()
+        .map(|cid| crate::deepsafe::storage().committee().committees(cid))
+        .collect::<Vec<_>>();
+    sub_client.query_storage_batch(stores, at_block).await
+}
+
 pub async fn committees_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<Committee<AccountId20, u32>>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().committees_root();
     sub_client
@@ -48,9 +71,18 @@ pub async fn committees_iter(
         .map(|res| res.into_iter().map(|v| v.1).collect())
 }
 
+pub async fn committees_diff(
+    sub_client: &DeepSafeSubClient,
+    from: At,
+    to: At,
+) -> Result<StorageDiff<Committee<AccountId20, u32>>, subxt::Error> {
+    let store = crate::deepsafe::storage().committee().committees_root();
+    sub_client.storage_diff(store, from, to).await
+}
+
 pub async fn snapshot(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<Vec<u8>>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().snapshot();
     sub_client
@@ -61,7 +93,7 @@ pub async fn snapshot(
 
 pub async fn snapshot_index(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u16>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().snapshot_index();
     sub_client
@@ -72,7 +104,7 @@ pub async fn snapshot_index(
 
 pub async fn snapshot_index_keygen(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u16>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
@@ -85,7 +117,7 @@ pub async fn snapshot_index_keygen(
 
 pub async fn candidate_pool(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<Vec<u8>>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().candidate_pool();
     sub_client
@@ -96,7 +128,7 @@ pub async fn candidate_pool(
 
 pub async fn candidate_index(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u16>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
@@ -109,7 +141,7 @@ pub async fn candidate_index(
 
 pub async fn candidate_index_keygen(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u16>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
@@ -125,7 +157,7 @@ pub async fn committee_members(
     cid: u32,
     epoch: u32,
     fork_id: u8,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<Vec<u8>>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
@@ -133,10 +165,21 @@ pub async fn committee_members(
     sub_client.query_storage(store, at_block).await
 }
 
+pub async fn committee_members_diff(
+    sub_client: &DeepSafeSubClient,
+    from: At,
+    to: At,
+) -> Result<StorageDiff<Vec<Vec<u8>>>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .committee()
+        .committee_members_root();
+    sub_client.storage_diff(store, from, to).await
+}
+
 pub async fn member_links(
     sub_client: &DeepSafeSubClient,
     member: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u32, subxt::Error> {
     let store = crate::deepsafe::storage().committee().member_links(member);
     sub_client
@@ -148,7 +191,7 @@ pub async fn member_links(
 pub async fn member_links_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<(Vec<u8>, u32)>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().member_links_root();
     sub_client
@@ -165,7 +208,7 @@ pub async fn candidate_links(
     sub_client: &DeepSafeSubClient,
     cid: u32,
     fork: u8,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u16>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
@@ -180,7 +223,7 @@ pub async fn epoch_change_failures(
     sub_client: &DeepSafeSubClient,
     cid: u32,
     fork: u8,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u8, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
@@ -194,7 +237,7 @@ pub async fn epoch_change_failures(
 pub async fn epoch_change_failures_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<(u32, u8, u8)>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
@@ -216,7 +259,7 @@ pub async fn epoch_change_failures_iter(
 pub async fn committee_randomness(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<u64>, subxt::Error> {
     let store = crate::deepsafe::storage().committee().c_randomness(cid);
     sub_client.query_storage(store, at_block).await
@@ -225,7 +268,7 @@ pub async fn committee_randomness(
 pub async fn identity_rewards(
     sub_client: &DeepSafeSubClient,
     ident: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u128, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
@@ -239,7 +282,7 @@ pub async fn identity_rewards(
 pub async fn exposed_identity(
     sub_client: &DeepSafeSubClient,
     ident: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u8>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee()
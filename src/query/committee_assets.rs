@@ -1,10 +1,10 @@
-use crate::DeepSafeSubClient;
+use crate::{At, DeepSafeSubClient};
 use crate::deepsafe::runtime_types::pallet_committee_assets::pallet::AssetConsensusInfo;
-use sp_core::H256 as Hash;
+use crate::storage::proof::StorageProofBundle;
 
 pub async fn all_concerned_brc20(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<Vec<u8>>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_assets()
@@ -15,7 +15,7 @@ pub async fn all_concerned_brc20(
 pub async fn brc20_decimals(
     sub_client: &DeepSafeSubClient,
     tick: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<u8>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_assets()
@@ -26,10 +26,21 @@ pub async fn brc20_decimals(
 pub async fn assets_consensus(
     sub_client: &DeepSafeSubClient,
     cid: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<AssetConsensusInfo>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_assets()
         .assets_consensus(cid);
     sub_client.query_storage(store, at_block).await
 }
+
+pub async fn assets_consensus_with_proof(
+    sub_client: &DeepSafeSubClient,
+    cid: u32,
+    at_block: At,
+) -> Result<(Option<AssetConsensusInfo>, StorageProofBundle), subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .committee_assets()
+        .assets_consensus(cid);
+    sub_client.query_storage_with_proof(store, at_block).await
+}
@@ -1,13 +1,14 @@
 use crate::deepsafe::runtime_types::pallet_committee_health::pallet::{
     ConfirmData, ConsensusStage, DHCState,
 };
-use crate::DeepSafeSubClient;
+use crate::{At, DeepSafeSubClient};
 use sp_core::H256 as Hash;
+use tokio::sync::mpsc::Receiver;
 
 pub async fn identity_challenge(
     sub_client: &DeepSafeSubClient,
     identity: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<(u32, Vec<u8>), subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_health()
@@ -20,7 +21,7 @@ pub async fn identity_challenge(
 
 pub async fn court_members(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<Vec<u8>>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_health()
@@ -30,7 +31,7 @@ pub async fn court_members(
 
 pub async fn consensus_state(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<DHCState>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_health()
@@ -38,10 +39,21 @@ pub async fn consensus_state(
     sub_client.query_storage(store, at_block).await
 }
 
+/// Receive '(block_hash, 0, state)' whenever the consensus state changes.
+pub fn subscribe_consensus_state(
+    sub_client: &DeepSafeSubClient,
+    buffer: usize,
+) -> Receiver<(Hash, usize, Option<DHCState>)> {
+    let store = crate::deepsafe::storage()
+        .committee_health()
+        .consensus_state();
+    sub_client.subscribe_storage(vec![store], buffer)
+}
+
 pub async fn state_votes(
     sub_client: &DeepSafeSubClient,
     device_id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u8>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_health()
@@ -56,7 +68,7 @@ pub async fn consensus_confirms(
     sub_client: &DeepSafeSubClient,
     epoch: u64,
     stage: ConsensusStage,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<ConfirmData>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_health()
@@ -66,7 +78,7 @@ pub async fn consensus_confirms(
 
 pub async fn submit_devices_whitelist(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<Vec<u8>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_health()
@@ -79,7 +91,7 @@ pub async fn submit_devices_whitelist(
 
 pub async fn submit_devices(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<Vec<u8>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_health()
@@ -92,7 +104,7 @@ pub async fn submit_devices(
 
 pub async fn submit_devices_size(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u16, subxt::Error> {
     let store = crate::deepsafe::storage()
         .committee_health()
@@ -1,9 +1,8 @@
-use crate::DeepSafeSubClient;
-use sp_core::H256 as Hash;
+use crate::{At, DeepSafeSubClient};
 
 pub async fn round_msg_wait(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<u64>, subxt::Error> {
     let store = crate::deepsafe::storage().configs().round_msg_wait();
     sub_client.query_storage(store, at_block).await
@@ -11,7 +10,7 @@ pub async fn round_msg_wait(
 
 pub async fn round_msg_request_limit(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<u8>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .configs()
@@ -22,7 +21,7 @@ pub async fn round_msg_request_limit(
 pub async fn monitor_delay_tolerance(
     sub_client: &DeepSafeSubClient,
     chain_id: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u64, subxt::Error> {
     let store = crate::deepsafe::storage()
         .configs()
@@ -35,7 +34,7 @@ pub async fn monitor_delay_tolerance(
 
 pub async fn monitor_delay_tolerance_iter(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<(u32, u64)>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .configs()
@@ -57,7 +56,7 @@ pub async fn monitor_delay_tolerance_iter(
 pub async fn device_url_map(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<u8>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().configs().device_url_map(id);
     sub_client.query_storage(storage_query, at_block).await
@@ -65,7 +64,7 @@ pub async fn device_url_map(
 
 pub async fn simple_sign(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<bool, subxt::Error> {
     let storage_query = crate::deepsafe::storage().configs().simple_sign();
     sub_client
@@ -76,7 +75,7 @@ pub async fn simple_sign(
 
 pub async fn simple_key(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<bool, subxt::Error> {
     let storage_query = crate::deepsafe::storage().configs().simple_key();
     sub_client
@@ -87,7 +86,7 @@ pub async fn simple_key(
 
 pub async fn device_heartbeat_interval(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<u64>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .configs()
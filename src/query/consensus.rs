@@ -0,0 +1,326 @@
+use crate::deepsafe::runtime_types::fp_account::AccountId20;
+use crate::deepsafe::runtime_types::node_runtime::opaque::SessionKeys;
+use crate::deepsafe::runtime_types::pallet_grandpa::StoredState;
+use crate::deepsafe::runtime_types::pallet_staking::{
+    ActiveEraInfo, EraRewardPoints, Exposure, Nominations, RewardDestination, StakingLedger,
+    ValidatorPrefs,
+};
+use crate::deepsafe::runtime_types::sp_consensus_babe::{
+    app::Public as BabeId, BabeEpochConfiguration,
+};
+use crate::deepsafe::runtime_types::sp_consensus_grandpa::app::Public as GrandpaId;
+use crate::{At, DeepSafeSubClient};
+
+pub async fn session_index(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<u32, subxt::Error> {
+    let store = crate::deepsafe::storage().session().current_index();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn session_validators(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Vec<AccountId20>, subxt::Error> {
+    let store = crate::deepsafe::storage().session().validators();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn disabled_validators(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Vec<u32>, subxt::Error> {
+    let store = crate::deepsafe::storage().session().disabled_validators();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn queued_changed(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<bool, subxt::Error> {
+    let store = crate::deepsafe::storage().session().queued_changed();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn next_keys(
+    sub_client: &DeepSafeSubClient,
+    validator: AccountId20,
+    at_block: At,
+) -> Result<Option<SessionKeys>, subxt::Error> {
+    let store = crate::deepsafe::storage().session().next_keys(validator);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn babe_epoch_index(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<u64, subxt::Error> {
+    let store = crate::deepsafe::storage().babe().epoch_index();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+/// '(block number of the current epoch start, block number of the previous epoch start)'.
+pub async fn babe_epoch_start(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<(u32, u32), subxt::Error> {
+    let store = crate::deepsafe::storage().babe().epoch_start();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn babe_current_slot(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<u64, subxt::Error> {
+    let store = crate::deepsafe::storage().babe().current_slot();
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn babe_genesis_slot(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<u64, subxt::Error> {
+    let store = crate::deepsafe::storage().babe().genesis_slot();
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+/// Randomness of the current epoch.
+pub async fn babe_randomness(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<[u8; 32], subxt::Error> {
+    let store = crate::deepsafe::storage().babe().randomness();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn babe_next_randomness(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<[u8; 32], subxt::Error> {
+    let store = crate::deepsafe::storage().babe().next_randomness();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn babe_authorities(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Vec<(BabeId, u64)>, subxt::Error> {
+    let store = crate::deepsafe::storage().babe().authorities();
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn babe_epoch_config(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Option<BabeEpochConfiguration>, subxt::Error> {
+    let store = crate::deepsafe::storage().babe().epoch_config();
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn grandpa_set_id(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<u64, subxt::Error> {
+    let store = crate::deepsafe::storage().grandpa().current_set_id();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn grandpa_authorities(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Vec<(GrandpaId, u64)>, subxt::Error> {
+    let store = crate::deepsafe::storage().grandpa().authorities();
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn grandpa_state(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<StoredState<u32>, subxt::Error> {
+    let store = crate::deepsafe::storage().grandpa().state();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+/// Session index in which the grandpa set id started.
+pub async fn grandpa_set_id_session(
+    sub_client: &DeepSafeSubClient,
+    set_id: u64,
+    at_block: At,
+) -> Result<Option<u32>, subxt::Error> {
+    let store = crate::deepsafe::storage().grandpa().set_id_session(set_id);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn current_era(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Option<u32>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().current_era();
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn active_era(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Option<ActiveEraInfo>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().active_era();
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn eras_start_session_index(
+    sub_client: &DeepSafeSubClient,
+    era: u32,
+    at_block: At,
+) -> Result<Option<u32>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .staking()
+        .eras_start_session_index(era);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn validator_count(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<u32, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().validator_count();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+/// Controller account of the stash.
+pub async fn bonded(
+    sub_client: &DeepSafeSubClient,
+    stash: AccountId20,
+    at_block: At,
+) -> Result<Option<AccountId20>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().bonded(stash);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn ledger(
+    sub_client: &DeepSafeSubClient,
+    controller: AccountId20,
+    at_block: At,
+) -> Result<Option<StakingLedger>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().ledger(controller);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn payee(
+    sub_client: &DeepSafeSubClient,
+    stash: AccountId20,
+    at_block: At,
+) -> Result<RewardDestination<AccountId20>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().payee(stash);
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn validator_prefs(
+    sub_client: &DeepSafeSubClient,
+    stash: AccountId20,
+    at_block: At,
+) -> Result<ValidatorPrefs, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().validators(stash);
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn validators_iter(
+    sub_client: &DeepSafeSubClient,
+    page_size: u32,
+    at_block: At,
+) -> Result<Vec<(AccountId20, ValidatorPrefs)>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().validators_root();
+    sub_client
+        .query_storage_value_iter(store, page_size, at_block)
+        .await
+        .map(|res| {
+            res.into_iter()
+                .map(|(key, value)| (account_from_twox64_key(&key.0), value))
+                .collect()
+        })
+}
+
+pub async fn nominations(
+    sub_client: &DeepSafeSubClient,
+    stash: AccountId20,
+    at_block: At,
+) -> Result<Option<Nominations>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().nominators(stash);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn nominators_iter(
+    sub_client: &DeepSafeSubClient,
+    page_size: u32,
+    at_block: At,
+) -> Result<Vec<(AccountId20, Nominations)>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().nominators_root();
+    sub_client
+        .query_storage_value_iter(store, page_size, at_block)
+        .await
+        .map(|res| {
+            res.into_iter()
+                .map(|(key, value)| (account_from_twox64_key(&key.0), value))
+                .collect()
+        })
+}
+
+pub async fn eras_stakers(
+    sub_client: &DeepSafeSubClient,
+    era: u32,
+    stash: AccountId20,
+    at_block: At,
+) -> Result<Exposure<AccountId20, u128>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .staking()
+        .eras_stakers(era, stash);
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn eras_reward_points(
+    sub_client: &DeepSafeSubClient,
+    era: u32,
+    at_block: At,
+) -> Result<EraRewardPoints<AccountId20>, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().eras_reward_points(era);
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn eras_validator_reward(
+    sub_client: &DeepSafeSubClient,
+    era: u32,
+    at_block: At,
+) -> Result<Option<u128>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .staking()
+        .eras_validator_reward(era);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn eras_total_stake(
+    sub_client: &DeepSafeSubClient,
+    era: u32,
+    at_block: At,
+) -> Result<u128, subxt::Error> {
+    let store = crate::deepsafe::storage().staking().eras_total_stake(era);
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+// storage key is 'twox128(pallet) ++ twox128(entry) ++ twox64(account) ++ account'
+fn account_from_twox64_key(key: &[u8]) -> AccountId20 {
+    let mut account = [0u8; 20];
+    account.copy_from_slice(&key[40..60]);
+    AccountId20(account)
+}
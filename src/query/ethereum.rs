@@ -1,10 +1,157 @@
-use crate::DeepSafeSubClient;
-use sp_core::H256 as Hash;
+use crate::deepsafe::runtime_types::ethereum::block::Block;
+use crate::deepsafe::runtime_types::ethereum::receipt::ReceiptV3;
+use crate::deepsafe::runtime_types::ethereum::transaction::TransactionV2;
+use crate::deepsafe::runtime_types::fp_rpc::TransactionStatus;
+use crate::types::{EvmAccount, EvmCallRequest, EvmExecutionInfo};
+use crate::{At, DeepSafeSubClient};
+use codec::Encode;
+use sp_core::{H160, U256};
+use sp_runtime::DispatchError;
 
 pub async fn evm_chain_id(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<u64>, subxt::Error> {
     let store = crate::deepsafe::storage().evm_chain_id().chain_id();
     sub_client.query_storage(store, at_block).await
 }
+
+/// The ethereum block built by the latest imported substrate block.
+pub async fn current_block(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Option<Block<TransactionV2>>, subxt::Error> {
+    let store = crate::deepsafe::storage().ethereum().current_block();
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn current_receipts(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Option<Vec<ReceiptV3>>, subxt::Error> {
+    let store = crate::deepsafe::storage().ethereum().current_receipts();
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn current_transaction_statuses(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Option<Vec<TransactionStatus>>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .ethereum()
+        .current_transaction_statuses();
+    sub_client.query_storage(store, at_block).await
+}
+
+/// Nonce and balance of the evm account.
+pub async fn account_basic(
+    sub_client: &DeepSafeSubClient,
+    address: H160,
+    at_block: At,
+) -> Result<EvmAccount, subxt::Error> {
+    sub_client
+        .runtime_api_call(
+            "EthereumRuntimeRPCApi_account_basic",
+            &address.encode(),
+            at_block,
+        )
+        .await
+}
+
+/// Minimum gas price of the runtime, which is the base fee of EIP-1559 tx.
+pub async fn gas_price(sub_client: &DeepSafeSubClient, at_block: At) -> Result<U256, subxt::Error> {
+    sub_client
+        .runtime_api_call("EthereumRuntimeRPCApi_gas_price", &[], at_block)
+        .await
+}
+
+/// Execute 'request' by runtime api 'EthereumRuntimeRPCApi_call' without changing the state,
+/// the returned data is the 'value' of the info. If 'estimate', the gas is metered as a real tx.
+pub async fn evm_call_raw(
+    sub_client: &DeepSafeSubClient,
+    request: &EvmCallRequest,
+    estimate: bool,
+    at_block: At,
+) -> Result<Result<EvmExecutionInfo<Vec<u8>>, DispatchError>, subxt::Error> {
+    let to = request
+        .to
+        .ok_or_else(|| subxt::Error::Other("evm call without target address".to_string()))?;
+    let gas_limit = request_gas_limit(sub_client, request, at_block).await?;
+    let mut params = (request.from, to).encode();
+    encode_call_params(request, gas_limit, estimate, &mut params);
+    sub_client
+        .runtime_api_call("EthereumRuntimeRPCApi_call", &params, at_block)
+        .await
+}
+
+/// Same as 'evm_call_raw' but create a contract by runtime api 'EthereumRuntimeRPCApi_create',
+/// the 'value' of the info is the contract address.
+pub async fn evm_create_raw(
+    sub_client: &DeepSafeSubClient,
+    request: &EvmCallRequest,
+    estimate: bool,
+    at_block: At,
+) -> Result<Result<EvmExecutionInfo<H160>, DispatchError>, subxt::Error> {
+    let gas_limit = request_gas_limit(sub_client, request, at_block).await?;
+    let mut params = request.from.encode();
+    encode_call_params(request, gas_limit, estimate, &mut params);
+    sub_client
+        .runtime_api_call("EthereumRuntimeRPCApi_create", &params, at_block)
+        .await
+}
+
+/// Gas used by 'request' executed as a real tx, or error if the execution fails.
+pub async fn estimate_gas(
+    sub_client: &DeepSafeSubClient,
+    request: &EvmCallRequest,
+    at_block: At,
+) -> Result<U256, subxt::Error> {
+    let (exit_reason, used_gas) = match request.to {
+        Some(_) => evm_call_raw(sub_client, request, true, at_block)
+            .await?
+            .map(|info| (info.exit_reason, info.used_gas)),
+        None => evm_create_raw(sub_client, request, true, at_block)
+            .await?
+            .map(|info| (info.exit_reason, info.used_gas)),
+    }
+    .map_err(|e| subxt::Error::Other(format!("estimate evm gas failed: {e:?}")))?;
+    match exit_reason {
+        crate::deepsafe::runtime_types::evm_core::error::ExitReason::Succeed(_) => {
+            Ok(used_gas.effective)
+        }
+        reason => Err(subxt::Error::Other(format!(
+            "estimate evm gas failed: {reason:?}"
+        ))),
+    }
+}
+
+async fn request_gas_limit(
+    sub_client: &DeepSafeSubClient,
+    request: &EvmCallRequest,
+    at_block: At,
+) -> Result<U256, subxt::Error> {
+    if let Some(gas_limit) = request.gas_limit {
+        return Ok(gas_limit);
+    }
+    current_block(sub_client, at_block)
+        .await?
+        .map(|block| U256(block.header.gas_limit.0))
+        .ok_or_else(|| subxt::Error::Other("no current ethereum block".to_string()))
+}
+
+// params after 'from' and 'to', shared by 'call' and 'create' of 'EthereumRuntimeRPCApi'
+fn encode_call_params(
+    request: &EvmCallRequest,
+    gas_limit: U256,
+    estimate: bool,
+    params: &mut Vec<u8>,
+) {
+    request.data.encode_to(params);
+    request.value.encode_to(params);
+    gas_limit.encode_to(params);
+    request.max_fee_per_gas.encode_to(params);
+    request.max_priority_fee_per_gas.encode_to(params);
+    request.nonce.encode_to(params);
+    estimate.encode_to(params);
+    request.access_list.encode_to(params);
+}
@@ -1,10 +1,9 @@
-use crate::DeepSafeSubClient;
-use sp_core::H256 as Hash;
+use crate::{At, DeepSafeSubClient};
 
 pub async fn hash_to_version(
     sub_client: &DeepSafeSubClient,
     version: u16,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<u8>>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .facility()
@@ -14,7 +13,7 @@ pub async fn hash_to_version(
 
 pub async fn version_list(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u16>, subxt::Error> {
     let store = crate::deepsafe::storage().facility().version_list();
     sub_client.query_storage_or_default(store, at_block).await
@@ -0,0 +1,251 @@
+use crate::deepsafe::runtime_types::fp_account::AccountId20;
+use crate::deepsafe::runtime_types::frame_support::traits::preimages::Bounded;
+use crate::deepsafe::runtime_types::node_runtime::{OriginCaller, RuntimeCall};
+use crate::deepsafe::runtime_types::pallet_collective::Votes;
+use crate::deepsafe::runtime_types::pallet_conviction_voting::{types::Tally, vote::Voting};
+use crate::deepsafe::runtime_types::pallet_referenda::types::ReferendumInfo;
+use crate::deepsafe::runtime_types::pallet_scheduler::Scheduled;
+use crate::deepsafe::runtime_types::sp_runtime::traits::BlakeTwo256;
+use crate::types::Collective;
+use crate::{At, DeepSafeSubClient};
+use sp_core::H256 as Hash;
+
+pub type ReferendumInfoOf = ReferendumInfo<
+    u16,
+    OriginCaller,
+    u32,
+    Bounded<RuntimeCall, BlakeTwo256>,
+    u128,
+    Tally<u128>,
+    AccountId20,
+    (u32, u32),
+>;
+
+pub type ScheduledOf =
+    Scheduled<[u8; 32], Bounded<RuntimeCall, BlakeTwo256>, u32, OriginCaller, AccountId20>;
+
+pub async fn sudo_key(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<Option<AccountId20>, subxt::Error> {
+    let store = crate::deepsafe::storage().sudo().key();
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn referendum_count(
+    sub_client: &DeepSafeSubClient,
+    at_block: At,
+) -> Result<u32, subxt::Error> {
+    let store = crate::deepsafe::storage().referenda().referendum_count();
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn referendum_info(
+    sub_client: &DeepSafeSubClient,
+    index: u32,
+    at_block: At,
+) -> Result<Option<ReferendumInfoOf>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .referenda()
+        .referendum_info_for(index);
+    sub_client.query_storage(store, at_block).await
+}
+
+/// Tally of an ongoing referendum, 'None' if it doesn't exist or is finished.
+pub async fn referendum_tally(
+    sub_client: &DeepSafeSubClient,
+    index: u32,
+    at_block: At,
+) -> Result<Option<Tally<u128>>, subxt::Error> {
+    referendum_info(sub_client, index, at_block)
+        .await
+        .map(|info| match info {
+            Some(ReferendumInfo::Ongoing(status)) => Some(status.tally),
+            _ => None,
+        })
+}
+
+pub async fn track_queue(
+    sub_client: &DeepSafeSubClient,
+    track: u16,
+    at_block: At,
+) -> Result<Vec<(u32, u128)>, subxt::Error> {
+    let store = crate::deepsafe::storage().referenda().track_queue(track);
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn deciding_count(
+    sub_client: &DeepSafeSubClient,
+    track: u16,
+    at_block: At,
+) -> Result<u32, subxt::Error> {
+    let store = crate::deepsafe::storage().referenda().deciding_count(track);
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn voting_for(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    class: u16,
+    at_block: At,
+) -> Result<Voting<u128, AccountId20, u32, u32>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .conviction_voting()
+        .voting_for(account, class);
+    sub_client.query_storage_or_default(store, at_block).await
+}
+
+pub async fn class_locks_for(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    at_block: At,
+) -> Result<Vec<(u16, u128)>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .conviction_voting()
+        .class_locks_for(account);
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn is_whitelisted_call(
+    sub_client: &DeepSafeSubClient,
+    call_hash: Hash,
+    at_block: At,
+) -> Result<bool, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .whitelist()
+        .whitelisted_call(call_hash);
+    sub_client
+        .query_storage(store, at_block)
+        .await
+        .map(|r| r.is_some())
+}
+
+pub async fn preimage_for(
+    sub_client: &DeepSafeSubClient,
+    hash: Hash,
+    len: u32,
+    at_block: At,
+) -> Result<Option<Vec<u8>>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .preimage()
+        .preimage_for((hash, len));
+    sub_client
+        .query_storage(store, at_block)
+        .await
+        .map(|r| r.map(|v| v.0))
+}
+
+pub async fn agenda(
+    sub_client: &DeepSafeSubClient,
+    block: u32,
+    at_block: At,
+) -> Result<Vec<Option<ScheduledOf>>, subxt::Error> {
+    let store = crate::deepsafe::storage().scheduler().agenda(block);
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+/// '(block, index)' of the named scheduled task.
+pub async fn scheduler_lookup(
+    sub_client: &DeepSafeSubClient,
+    id: [u8; 32],
+    at_block: At,
+) -> Result<Option<(u32, u32)>, subxt::Error> {
+    let store = crate::deepsafe::storage().scheduler().lookup(id);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn collective_members(
+    sub_client: &DeepSafeSubClient,
+    collective: Collective,
+    at_block: At,
+) -> Result<Vec<AccountId20>, subxt::Error> {
+    match collective {
+        Collective::TreasuryCouncil => {
+            let store = crate::deepsafe::storage()
+                .treasury_council_collective()
+                .members();
+            sub_client.query_storage_or_default(store, at_block).await
+        }
+        Collective::OpenTechCommittee => {
+            let store = crate::deepsafe::storage()
+                .open_tech_committee_collective()
+                .members();
+            sub_client.query_storage_or_default(store, at_block).await
+        }
+    }
+}
+
+pub async fn collective_proposals(
+    sub_client: &DeepSafeSubClient,
+    collective: Collective,
+    at_block: At,
+) -> Result<Vec<Hash>, subxt::Error> {
+    match collective {
+        Collective::TreasuryCouncil => {
+            let store = crate::deepsafe::storage()
+                .treasury_council_collective()
+                .proposals();
+            sub_client.query_storage_or_default(store, at_block).await
+        }
+        Collective::OpenTechCommittee => {
+            let store = crate::deepsafe::storage()
+                .open_tech_committee_collective()
+                .proposals();
+            sub_client.query_storage_or_default(store, at_block).await
+        }
+    }
+    .map(|r| r.0)
+}
+
+pub async fn collective_proposal_of(
+    sub_client: &DeepSafeSubClient,
+    collective: Collective,
+    proposal_hash: Hash,
+    at_block: At,
+) -> Result<Option<RuntimeCall>, subxt::Error> {
+    match collective {
+        Collective::TreasuryCouncil => {
+            let store = crate::deepsafe::storage()
+                .treasury_council_collective()
+                .proposal_of(proposal_hash);
+            sub_client.query_storage(store, at_block).await
+        }
+        Collective::OpenTechCommittee => {
+            let store = crate::deepsafe::storage()
+                .open_tech_committee_collective()
+                .proposal_of(proposal_hash);
+            sub_client.query_storage(store, at_block).await
+        }
+    }
+}
+
+pub async fn collective_voting(
+    sub_client: &DeepSafeSubClient,
+    collective: Collective,
+    proposal_hash: Hash,
+    at_block: At,
+) -> Result<Option<Votes<AccountId20, u32>>, subxt::Error> {
+    match collective {
+        Collective::TreasuryCouncil => {
+            let store = crate::deepsafe::storage()
+                .treasury_council_collective()
+                .voting(proposal_hash);
+            sub_client.query_storage(store, at_block).await
+        }
+        Collective::OpenTechCommittee => {
+            let store = crate::deepsafe::storage()
+                .open_tech_committee_collective()
+                .voting(proposal_hash);
+            sub_client.query_storage(store, at_block).await
+        }
+    }
+}
@@ -5,13 +5,13 @@ use crate::deepsafe::runtime_types::{
     primitive_types::U256,
     sp_arithmetic::per_things::Perbill,
 };
-use crate::DeepSafeSubClient;
-use sp_core::H256 as Hash;
+use crate::storage::StorageDiff;
+use crate::{At, DeepSafeSubClient};
 
 pub async fn challenges(
     sub_client: &DeepSafeSubClient,
     session: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<U256>, subxt::Error> {
     let store = crate::deepsafe::storage().mining().challenges(session);
     sub_client.query_storage(store, at_block).await
@@ -20,8 +20,10 @@ pub async fn challenges(
 pub async fn working_devices(
     sub_client: &DeepSafeSubClient,
     session: Option<u32>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<(Vec<(DIdentity, bool)>, u32)>, subxt::Error> {
+    // resolve once, so that session and working devices are read at the same block
+    let at_block = sub_client.resolve_at(at_block).await?;
     let session = match session {
         Some(session) => session,
         None => {
@@ -40,7 +42,7 @@ pub async fn working_devices(
     };
     let store = crate::deepsafe::storage().mining().working_devices(session);
     sub_client
-        .query_storage(store, at_block)
+        .query_storage(store, at_block.into())
         .await
         .map(|res| res.and_then(|data| Some((data, session))))
 }
@@ -48,7 +50,7 @@ pub async fn working_devices(
 pub async fn device_info(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<DeviceInfo<AccountId20, u32, u128>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().mining().devices(id.clone());
     sub_client.query_storage(storage_query, at_block).await
@@ -56,7 +58,7 @@ pub async fn device_info(
 
 pub async fn device_info_iter(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<DeviceInfo<AccountId20, u32, u128>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().mining().devices_root();
     sub_client
@@ -68,7 +70,7 @@ pub async fn device_info_iter(
 pub async fn device_identity_map(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<u8>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().mining().device_identity_map(id);
     sub_client.query_storage(storage_query, at_block).await
@@ -77,7 +79,7 @@ pub async fn device_identity_map(
 pub async fn device_identity_map_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, subxt::Error> {
     let storage_query = crate::deepsafe::storage()
         .mining()
@@ -95,7 +97,7 @@ pub async fn device_identity_map_iter(
 pub async fn device_monitor_state(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<MonitorState>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().mining().device_monitor_state(id);
     sub_client.query_storage(storage_query, at_block).await
@@ -104,7 +106,7 @@ pub async fn device_monitor_state(
 pub async fn device_stake_for_current_epoch(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<u128>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().mining().device_stake_for_current_epoch(id.clone());
     sub_client.query_storage(storage_query, at_block).await
@@ -113,7 +115,7 @@ pub async fn device_stake_for_current_epoch(
 pub async fn device_votes_for_current_epoch(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<(AccountId20, u128)>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage()
         .mining()
@@ -124,7 +126,7 @@ pub async fn device_votes_for_current_epoch(
 pub async fn device_votes_for_next_epoch(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<(AccountId20, u128)>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage()
         .mining()
@@ -135,7 +137,7 @@ pub async fn device_votes_for_next_epoch(
 pub async fn device_data(
     sub_client: &DeepSafeSubClient,
     did: DIdentity,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<u8>>, subxt::Error> {
     let store = crate::deepsafe::storage().mining().device_data(did.clone());
     sub_client.query_storage(store, at_block).await
@@ -144,7 +146,7 @@ pub async fn device_data(
 pub async fn devices_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<DeviceInfo<AccountId20, u32, u128>>, subxt::Error> {
     let store = crate::deepsafe::storage().mining().devices_root();
     sub_client
@@ -153,10 +155,19 @@ pub async fn devices_iter(
         .map(|res| res.into_iter().map(|(_, v)| v).collect())
 }
 
+pub async fn devices_diff(
+    sub_client: &DeepSafeSubClient,
+    from: At,
+    to: At,
+) -> Result<StorageDiff<DeviceInfo<AccountId20, u32, u128>>, subxt::Error> {
+    let store = crate::deepsafe::storage().mining().devices_root();
+    sub_client.storage_diff(store, from, to).await
+}
+
 pub async fn device_register_data(
     sub_client: &DeepSafeSubClient,
     device_id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<RegisterData>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .mining()
@@ -167,7 +178,7 @@ pub async fn device_register_data(
 pub async fn device_register_data_iter(
     sub_client: &DeepSafeSubClient,
     page_size: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<(Vec<u8>, RegisterData)>, subxt::Error> {
     let store = crate::deepsafe::storage()
         .mining()
@@ -184,7 +195,7 @@ pub async fn device_register_data_iter(
 
 pub async fn foundation(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<AccountId20>, subxt::Error> {
     let store = crate::deepsafe::storage().mining().foundation();
     sub_client.query_storage(store, at_block).await
@@ -192,7 +203,7 @@ pub async fn foundation(
 
 pub async fn foundation_reward_rate(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Perbill, subxt::Error> {
     let store = crate::deepsafe::storage().mining().foundation_reward_rate();
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or(Perbill(750_000_000)))
@@ -200,7 +211,7 @@ pub async fn foundation_reward_rate(
 
 pub async fn base_reward_rate(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Perbill, subxt::Error> {
     let store = crate::deepsafe::storage().mining().base_reward_rate();
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or(Perbill(100_000_000)))
@@ -209,7 +220,7 @@ pub async fn base_reward_rate(
 pub async fn rewards_for_epoch(
     sub_client: &DeepSafeSubClient,
     epoch: u64,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u128, subxt::Error> {
     let store = crate::deepsafe::storage().mining().rewards_for_epoch(epoch);
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or_default())
@@ -218,7 +229,7 @@ pub async fn rewards_for_epoch(
 pub async fn incentive_rewards_for_epoch(
     sub_client: &DeepSafeSubClient,
     epoch: u64,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u128, subxt::Error> {
     let store = crate::deepsafe::storage().mining().incentive_rewards_for_epoch(epoch);
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or_default())
@@ -226,7 +237,7 @@ pub async fn incentive_rewards_for_epoch(
 
 pub async fn number_of_pay_rewards_in_one_block(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u64, subxt::Error> {
     let store = crate::deepsafe::storage().mining().number_of_pay_rewards_in_one_block();
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or_default())
@@ -235,7 +246,7 @@ pub async fn number_of_pay_rewards_in_one_block(
 pub async fn device_stake_map(
     sub_client: &DeepSafeSubClient,
     device_id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u8>, subxt::Error> {
     let store = crate::deepsafe::storage().mining().device_stake_map(device_id.clone());
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or(device_id))
@@ -244,7 +255,7 @@ pub async fn device_stake_map(
 pub async fn stake_device_map(
     sub_client: &DeepSafeSubClient,
     stake_id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<u8>, subxt::Error> {
     let store = crate::deepsafe::storage().mining().stake_device_map(stake_id.clone());
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or(stake_id))
@@ -254,7 +265,7 @@ pub async fn rewards_from_committee(
     sub_client: &DeepSafeSubClient,
     device_id: Vec<u8>,
     epoch: u64,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<(u128, u128), subxt::Error> {
     let store = crate::deepsafe::storage().mining().rewards_from_committee(device_id, epoch);
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or((0, 0)))
@@ -263,7 +274,7 @@ pub async fn rewards_from_committee(
 pub async fn total_score_for_epoch(
     sub_client: &DeepSafeSubClient,
     epoch: u64,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u128, subxt::Error> {
     let store = crate::deepsafe::storage().mining().total_score_for_epoch(epoch);
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or_default())
@@ -273,7 +284,7 @@ pub async fn scores_for_epoch(
     sub_client: &DeepSafeSubClient,
     device_id: Vec<u8>,
     epoch: u64,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u128, subxt::Error> {
     let store = crate::deepsafe::storage().mining().scores_for_epoch(device_id, epoch);
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or_default())
@@ -282,7 +293,7 @@ pub async fn scores_for_epoch(
 pub async fn total_committee_score_for_epoch(
     sub_client: &DeepSafeSubClient,
     epoch: u64,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<u128, subxt::Error> {
     let store = crate::deepsafe::storage().mining().total_committee_score_for_epoch(epoch);
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or_default())
@@ -291,7 +302,7 @@ pub async fn total_committee_score_for_epoch(
 pub async fn committee_scores_for_epoch(
     sub_client: &DeepSafeSubClient,
     epoch: u64,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<(Vec<u8>, u32, u128)>>, subxt::Error> {
     let store = crate::deepsafe::storage().mining().committee_scores_for_epoch(epoch);
     sub_client.query_storage(store, at_block).await
@@ -300,7 +311,7 @@ pub async fn committee_scores_for_epoch(
 pub async fn device_ids_waiting_pay_rewards_for_epoch(
     sub_client: &DeepSafeSubClient,
     epoch: u64,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Vec<Vec<u8>>, subxt::Error> {
     let store = crate::deepsafe::storage().mining().device_ids_waiting_pay_rewards_for_epoch(epoch);
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or_default())
@@ -309,7 +320,7 @@ pub async fn device_ids_waiting_pay_rewards_for_epoch(
 pub async fn device_commission_for_current_epoch(
     sub_client: &DeepSafeSubClient,
     device_id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Perbill, subxt::Error> {
     let store = crate::deepsafe::storage().mining().device_commission_for_current_epoch(device_id);
     sub_client.query_storage(store, at_block).await.map(|r| r.unwrap_or(Perbill(150_000_000)))
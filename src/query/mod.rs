@@ -1,11 +1,15 @@
+pub mod balances;
 pub mod channel;
 pub mod committee;
 pub mod committee_assets;
 pub mod committee_health;
 pub mod configs;
+pub mod consensus;
 pub mod ethereum;
 pub mod facility;
+pub mod governance;
 pub mod mining;
+pub mod multisig;
 pub mod rpc;
 pub mod system;
 pub mod timestamp;
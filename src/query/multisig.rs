@@ -0,0 +1,87 @@
+use crate::deepsafe::runtime_types::fp_account::AccountId20;
+use crate::deepsafe::runtime_types::pallet_multisig::{Multisig, MultisigOrigin};
+use crate::{At, DeepSafeSubClient};
+
+pub async fn multisigs(
+    sub_client: &DeepSafeSubClient,
+    multisig: AccountId20,
+    call_hash: [u8; 32],
+    at_block: At,
+) -> Result<Option<Multisig<u32, u128, AccountId20>>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .multisig()
+        .multisigs(multisig, call_hash);
+    sub_client.query_storage(store, at_block).await
+}
+
+pub async fn multisigs_for_account(
+    sub_client: &DeepSafeSubClient,
+    account: AccountId20,
+    at_block: At,
+) -> Result<Vec<MultisigOrigin<AccountId20>>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .multisig()
+        .multisigs_for_account(account);
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn unexecuted_calls(
+    sub_client: &DeepSafeSubClient,
+    multisig: AccountId20,
+    at_block: At,
+) -> Result<Vec<[u8; 32]>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .multisig()
+        .unexecuted_calls(multisig);
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+pub async fn executed_calls(
+    sub_client: &DeepSafeSubClient,
+    multisig: AccountId20,
+    at_block: At,
+) -> Result<Vec<[u8; 32]>, subxt::Error> {
+    let store = crate::deepsafe::storage()
+        .multisig()
+        .executed_calls(multisig);
+    sub_client
+        .query_storage_or_default(store, at_block)
+        .await
+        .map(|r| r.0)
+}
+
+/// Pending operations of the multisig with call hash, the 'when' field of
+/// 'Multisig' is the timepoint needed to approve or cancel it.
+pub async fn pending_multisigs(
+    sub_client: &DeepSafeSubClient,
+    multisig: AccountId20,
+    at_block: At,
+) -> Result<Vec<([u8; 32], Multisig<u32, u128, AccountId20>)>, subxt::Error> {
+    // resolve once, so that call hashes and operations are read at the same block
+    let at_block = At::Hash(sub_client.resolve_block_hash(at_block).await?);
+    let call_hashes = unexecuted_calls(sub_client, multisig.clone(), at_block).await?;
+    let stores = call_hashes
+        .iter()
+        .map(|call_hash| {
+            crate::deepsafe::storage()
+                .multisig()
+                .multisigs(multisig.clone(), *call_hash)
+        })
+        .collect::<Vec<_>>();
+    let operations = sub_client.query_storage_batch(stores, at_block).await?;
+    Ok(call_hashes
+        .into_iter()
+        .zip(operations)
+        .filter_map(|(call_hash, operation)| {
+            operation
+                .filter(|operation| !operation.finished)
+                .map(|operation| (call_hash, operation))
+        })
+        .collect())
+}
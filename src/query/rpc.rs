@@ -1,12 +1,11 @@
 use crate::deepsafe::runtime_types::fp_account::AccountId20 as RuntimeAccountId20;
 use crate::deepsafe::runtime_types::pallet_rpc::pallet::DeviceInfo;
-use crate::DeepSafeSubClient;
-use sp_core::H256 as Hash;
+use crate::{At, DeepSafeSubClient};
 
 pub async fn device_info_rpc(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<DeviceInfo<RuntimeAccountId20, u32>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().rpc().devices(id.clone());
     sub_client.query_storage(storage_query, at_block).await
@@ -15,7 +14,7 @@ pub async fn device_info_rpc(
 pub async fn relate_deviceid_rpc(
     sub_client: &DeepSafeSubClient,
     id: Vec<u8>,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<Vec<u8>>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage()
         .rpc()
@@ -25,7 +24,7 @@ pub async fn relate_deviceid_rpc(
 
 pub async fn eth_checkpoint(
     sub_client: &DeepSafeSubClient,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Vec<u8>>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().rpc().eth_checkpoint();
     sub_client.query_storage(storage_query, at_block).await
@@ -1,10 +1,10 @@
-use crate::DeepSafeSubClient;
+use crate::{At, DeepSafeSubClient};
 use sp_core::H256 as Hash;
 
 pub async fn block_hash(
     sub_client: &DeepSafeSubClient,
     height: u32,
-    at_block: Option<Hash>,
+    at_block: At,
 ) -> Result<Option<Hash>, subxt::Error> {
     let storage_query = crate::deepsafe::storage().system().block_hash(height);
     sub_client.query_storage(storage_query, at_block).await
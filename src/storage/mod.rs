@@ -0,0 +1,318 @@
+//! Helpers to work with raw storage keys and values of static storage addresses.
+pub mod proof;
+
+use sp_core::H256 as Hash;
+use std::collections::BTreeMap;
+use subxt::metadata::{
+    types::{StorageEntryMetadata, StorageEntryType},
+    DecodeWithMetadata, Metadata,
+};
+use subxt::storage::{address::Yes, StorageAddress, StorageKey};
+use subxt::Error;
+
+/// Build the full storage key bytes for the given address,
+/// 'twox128(pallet) ++ twox128(entry) ++ hashed keys'.
+pub fn storage_key_bytes<F: StorageAddress>(
+    address: &F,
+    metadata: &Metadata,
+) -> Result<Vec<u8>, Error> {
+    let mut bytes = sp_core::twox_128(address.pallet_name().as_bytes()).to_vec();
+    bytes.extend(sp_core::twox_128(address.entry_name().as_bytes()));
+    address.append_entry_bytes(metadata, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Decode raw storage value bytes into the target type of the given address.
+pub fn decode_storage_value<F: StorageAddress>(
+    address: &F,
+    bytes: &[u8],
+    metadata: &Metadata,
+) -> Result<F::Target, Error> {
+    let entry = storage_entry(address, metadata)?;
+    let type_id = match entry.entry_type() {
+        StorageEntryType::Plain(ty) => *ty,
+        StorageEntryType::Map { value_ty, .. } => *value_ty,
+    };
+    <F::Target as DecodeWithMetadata>::decode_with_metadata(&mut &*bytes, type_id, metadata)
+}
+
+/// Decode the default value of the storage entry, used when the storage doesn't exist.
+pub fn decode_storage_default<F: StorageAddress<IsDefaultable = Yes>>(
+    address: &F,
+    metadata: &Metadata,
+) -> Result<F::Target, Error> {
+    let default_bytes = storage_entry(address, metadata)?.default_bytes().to_vec();
+    decode_storage_value(address, &default_bytes, metadata)
+}
+
+fn storage_entry<'a, F: StorageAddress>(
+    address: &F,
+    metadata: &'a Metadata,
+) -> Result<&'a StorageEntryMetadata, Error> {
+    metadata
+        .pallet_by_name(address.pallet_name())
+        .and_then(|pallet| pallet.storage())
+        .and_then(|storage| storage.entry_by_name(address.entry_name()))
+        .ok_or_else(|| {
+            Error::Other(format!(
+                "storage entry {}::{} not found in metadata",
+                address.pallet_name(),
+                address.entry_name()
+            ))
+        })
+}
+
+/// A set of storage addresses which could be fetched in one round-trip,
+/// see `SubClient::query_storage_batch`.
+///
+/// Implemented for `Vec<F>` of the same address type, and for tuples of (up to 8)
+/// different address types, so that every result keeps its own typed value.
+pub trait StorageBatch {
+    type Target;
+
+    /// Storage keys of all addresses in the batch, in order.
+    fn storage_keys(&self, metadata: &Metadata) -> Result<Vec<Vec<u8>>, Error>;
+
+    /// Decode values returned for 'storage_keys', in the same order.
+    fn decode_values(
+        &self,
+        values: Vec<Option<Vec<u8>>>,
+        metadata: &Metadata,
+    ) -> Result<Self::Target, Error>;
+}
+
+impl<F: StorageAddress<IsFetchable = Yes>> StorageBatch for Vec<F> {
+    type Target = Vec<Option<F::Target>>;
+
+    fn storage_keys(&self, metadata: &Metadata) -> Result<Vec<Vec<u8>>, Error> {
+        self.iter()
+            .map(|address| storage_key_bytes(address, metadata))
+            .collect()
+    }
+
+    fn decode_values(
+        &self,
+        values: Vec<Option<Vec<u8>>>,
+        metadata: &Metadata,
+    ) -> Result<Self::Target, Error> {
+        self.iter()
+            .zip(values)
+            .map(|(address, value)| {
+                value
+                    .map(|bytes| decode_storage_value(address, &bytes, metadata))
+                    .transpose()
+            })
+            .collect()
+    }
+}
+
+macro_rules! impl_storage_batch_for_tuple {
+    ($($name:ident : $index:tt),+) => {
+        impl<$($name: StorageAddress<IsFetchable = Yes>),+> StorageBatch for ($($name,)+) {
+            type Target = ($(Option<$name::Target>,)+);
+
+            fn storage_keys(&self, metadata: &Metadata) -> Result<Vec<Vec<u8>>, Error> {
+                Ok(vec![$(storage_key_bytes(&self.$index, metadata)?),+])
+            }
+
+            fn decode_values(
+                &self,
+                values: Vec<Option<Vec<u8>>>,
+                metadata: &Metadata,
+            ) -> Result<Self::Target, Error> {
+                let mut values = values.into_iter();
+                Ok(($(
+                    values
+                        .next()
+                        .flatten()
+                        .map(|bytes| decode_storage_value(&self.$index, &bytes, metadata))
+                        .transpose()?,
+                )+))
+            }
+        }
+    };
+}
+
+impl_storage_batch_for_tuple!(A: 0);
+impl_storage_batch_for_tuple!(A: 0, B: 1);
+impl_storage_batch_for_tuple!(A: 0, B: 1, C: 2);
+impl_storage_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_storage_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_storage_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5);
+impl_storage_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5, H: 6);
+impl_storage_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5, H: 6, I: 7);
+
+/// Typed changes of the entries under one storage prefix between two blocks,
+/// see `SubClient::storage_diff`.
+#[derive(Clone, Debug)]
+pub struct StorageDiff<T> {
+    pub from: Hash,
+    pub to: Hash,
+    pub added: Vec<(StorageKey, T)>,
+    pub removed: Vec<(StorageKey, T)>,
+    // (key, value at 'from', value at 'to')
+    pub modified: Vec<(StorageKey, T, T)>,
+}
+
+impl<T> StorageDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Changes between two sets of raw storage entries, as '(added, removed, modified)' keys.
+pub fn diff_raw_entries(
+    from: &BTreeMap<Vec<u8>, Vec<u8>>,
+    to: &BTreeMap<Vec<u8>, Vec<u8>>,
+) -> (Vec<Vec<u8>>, Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let added = to
+        .keys()
+        .filter(|key| !from.contains_key(*key))
+        .cloned()
+        .collect();
+    let removed = from
+        .keys()
+        .filter(|key| !to.contains_key(*key))
+        .cloned()
+        .collect();
+    let modified = to
+        .iter()
+        .filter(|(key, value)| from.get(*key).is_some_and(|old| old != *value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    (added, removed, modified)
+}
+
+/// Decode the raw changes of 'prefix' into a typed 'StorageDiff'.
+pub fn decode_storage_diff<F: StorageAddress>(
+    prefix: &F,
+    (from_hash, from): (Hash, BTreeMap<Vec<u8>, Vec<u8>>),
+    (to_hash, to): (Hash, BTreeMap<Vec<u8>, Vec<u8>>),
+    metadata: &Metadata,
+) -> Result<StorageDiff<F::Target>, Error> {
+    let (added, removed, modified) = diff_raw_entries(&from, &to);
+    let decode = |entries: &BTreeMap<Vec<u8>, Vec<u8>>, key: &Vec<u8>| {
+        decode_storage_value(prefix, &entries[key], metadata)
+    };
+    Ok(StorageDiff {
+        from: from_hash,
+        to: to_hash,
+        added: added
+            .into_iter()
+            .map(|key| Ok((StorageKey(key.clone()), decode(&to, &key)?)))
+            .collect::<Result<_, Error>>()?,
+        removed: removed
+            .into_iter()
+            .map(|key| Ok((StorageKey(key.clone()), decode(&from, &key)?)))
+            .collect::<Result<_, Error>>()?,
+        modified: modified
+            .into_iter()
+            .map(|key| {
+                Ok((
+                    StorageKey(key.clone()),
+                    decode(&from, &key)?,
+                    decode(&to, &key)?,
+                ))
+            })
+            .collect::<Result<_, Error>>()?,
+    })
+}
+
+/// Raw entries under one storage prefix at the two compared blocks.
+pub type PrefixEntries = (BTreeMap<Vec<u8>, Vec<u8>>, BTreeMap<Vec<u8>, Vec<u8>>);
+
+/// A set of storage prefixes which could be diffed between the same two blocks,
+/// see `SubClient::storage_diffs`.
+///
+/// Implemented for `Vec<F>` of the same address type, and for tuples of (up to 8)
+/// different address types, so that every diff keeps its own typed value.
+pub trait StorageDiffBatch {
+    type Target;
+
+    /// Key bytes of all prefixes in the batch, in order.
+    fn prefix_keys(&self, metadata: &Metadata) -> Result<Vec<Vec<u8>>, Error>;
+
+    /// Decode entries fetched for 'prefix_keys', in the same order.
+    fn decode_diffs(
+        &self,
+        entries: Vec<PrefixEntries>,
+        (from, to): (Hash, Hash),
+        metadata: &Metadata,
+    ) -> Result<Self::Target, Error>;
+}
+
+impl<F: StorageAddress<IsIterable = Yes>> StorageDiffBatch for Vec<F> {
+    type Target = Vec<StorageDiff<F::Target>>;
+
+    fn prefix_keys(&self, metadata: &Metadata) -> Result<Vec<Vec<u8>>, Error> {
+        self.iter()
+            .map(|prefix| storage_key_bytes(prefix, metadata))
+            .collect()
+    }
+
+    fn decode_diffs(
+        &self,
+        entries: Vec<PrefixEntries>,
+        (from, to): (Hash, Hash),
+        metadata: &Metadata,
+    ) -> Result<Self::Target, Error> {
+        self.iter()
+            .zip(entries)
+            .map(|(prefix, (from_entries, to_entries))| {
+                decode_storage_diff(prefix, (from, from_entries), (to, to_entries), metadata)
+            })
+            .collect()
+    }
+}
+
+macro_rules! impl_storage_diff_batch_for_tuple {
+    ($($name:ident : $index:tt),+) => {
+        impl<$($name: StorageAddress<IsIterable = Yes>),+> StorageDiffBatch for ($($name,)+) {
+            type Target = ($(StorageDiff<$name::Target>,)+);
+
+            fn prefix_keys(&self, metadata: &Metadata) -> Result<Vec<Vec<u8>>, Error> {
+                Ok(vec![$(storage_key_bytes(&self.$index, metadata)?),+])
+            }
+
+            fn decode_diffs(
+                &self,
+                entries: Vec<PrefixEntries>,
+                (from, to): (Hash, Hash),
+                metadata: &Metadata,
+            ) -> Result<Self::Target, Error> {
+                let mut entries = entries.into_iter();
+                Ok(($({
+                    let (from_entries, to_entries) = entries.next().unwrap_or_default();
+                    decode_storage_diff(&self.$index, (from, from_entries), (to, to_entries), metadata)?
+                },)+))
+            }
+        }
+    };
+}
+
+impl_storage_diff_batch_for_tuple!(A: 0);
+impl_storage_diff_batch_for_tuple!(A: 0, B: 1);
+impl_storage_diff_batch_for_tuple!(A: 0, B: 1, C: 2);
+impl_storage_diff_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_storage_diff_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_storage_diff_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5);
+impl_storage_diff_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5, H: 6);
+impl_storage_diff_batch_for_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5, H: 6, I: 7);
+
+#[test]
+fn test_diff_raw_entries() {
+    let from = BTreeMap::from([
+        (vec![1], vec![10]),
+        (vec![2], vec![20]),
+        (vec![3], vec![30]),
+    ]);
+    let to = BTreeMap::from([
+        (vec![2], vec![20]),
+        (vec![3], vec![31]),
+        (vec![4], vec![40]),
+    ]);
+    assert_eq!(
+        diff_raw_entries(&from, &to),
+        (vec![vec![4]], vec![vec![1]], vec![vec![3]])
+    );
+}
@@ -0,0 +1,128 @@
+//! Storage read proofs, verified locally against the state root of the block header.
+use crate::client::DeepSafeConfig;
+use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sp_core::{Blake2Hasher, H256 as Hash};
+use sp_trie::{read_trie_value, LayoutV1, StorageProof};
+use subxt::Config;
+
+type Header = <DeepSafeConfig as Config>::Header;
+
+/// A portable proof that the storage 'key' has 'value' at block 'block_hash',
+/// it could be verified again without any node by 'verify'.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct StorageProofBundle {
+    pub block_hash: Hash,
+    pub block_number: u32,
+    // SCALE encoded block header, whose hash is 'block_hash'
+    pub header: Vec<u8>,
+    pub key: Vec<u8>,
+    // 'None' proves the storage doesn't exist at the block
+    pub value: Option<Vec<u8>>,
+    pub proof: Vec<Vec<u8>>,
+}
+
+impl StorageProofBundle {
+    pub fn new(header: &Header, key: Vec<u8>, proof: Vec<Vec<u8>>) -> Result<Self, String> {
+        let header_bytes = header.encode();
+        let value = verify_read_proof(header.state_root, &key, proof.clone())?;
+        Ok(StorageProofBundle {
+            block_hash: Hash(sp_core::blake2_256(&header_bytes)),
+            block_number: header.number,
+            header: header_bytes,
+            key,
+            value,
+            proof,
+        })
+    }
+
+    /// Same as 'new' but fails if the header isn't the one of the requested 'block',
+    /// otherwise the bundle is only consistent with itself.
+    pub fn new_at(
+        block: Hash,
+        header: &Header,
+        key: Vec<u8>,
+        proof: Vec<Vec<u8>>,
+    ) -> Result<Self, String> {
+        let bundle = Self::new(header, key, proof)?;
+        if bundle.block_hash != block {
+            return Err(format!(
+                "header of block {:?} doesn't match the requested block {block:?}",
+                bundle.block_hash
+            ));
+        }
+        Ok(bundle)
+    }
+
+    /// Check the header matches 'block_hash' and the proof matches the state root of the header.
+    pub fn verify(&self) -> Result<(), String> {
+        if sp_core::blake2_256(&self.header) != self.block_hash.0 {
+            return Err(format!(
+                "header doesn't match block hash {:?}",
+                self.block_hash
+            ));
+        }
+        let header = Header::decode(&mut self.header.as_slice()).map_err(|e| e.to_string())?;
+        if header.number != self.block_number {
+            return Err(format!(
+                "header number {} doesn't match block number {}",
+                header.number, self.block_number
+            ));
+        }
+        let value = verify_read_proof(header.state_root, &self.key, self.proof.clone())?;
+        if value != self.value {
+            return Err(format!(
+                "proved value doesn't match for key 0x{}",
+                hex::encode(&self.key)
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Read the value of 'key' from the proof nodes, fails if the proof is incomplete
+/// or doesn't belong to 'state_root'.
+pub fn verify_read_proof(
+    state_root: Hash,
+    key: &[u8],
+    proof: Vec<Vec<u8>>,
+) -> Result<Option<Vec<u8>>, String> {
+    let db = StorageProof::new(proof).into_memory_db::<Blake2Hasher>();
+    read_trie_value::<LayoutV1<Blake2Hasher>, _>(&db, &state_root, key, None, None)
+        .map_err(|e| format!("verify read proof failed for: {e:?}"))
+}
+
+#[test]
+fn test_proof_bundle_at_block() {
+    use sp_trie::{MemoryDB, TrieDBMutBuilder, TrieMut};
+
+    let mut db = MemoryDB::<Blake2Hasher>::default();
+    let mut state_root = Hash::zero();
+    {
+        let mut trie =
+            TrieDBMutBuilder::<LayoutV1<Blake2Hasher>>::new(&mut db, &mut state_root).build();
+        trie.insert(b"key", b"value").unwrap();
+    }
+    let proof = db
+        .drain()
+        .into_values()
+        .map(|(node, _)| node)
+        .collect::<Vec<_>>();
+    let header = Header {
+        parent_hash: Hash::repeat_byte(1),
+        number: 10,
+        state_root,
+        extrinsics_root: Hash::zero(),
+        digest: Default::default(),
+    };
+    let block = Hash(sp_core::blake2_256(&header.encode()));
+
+    let bundle =
+        StorageProofBundle::new_at(block, &header, b"key".to_vec(), proof.clone()).unwrap();
+    assert_eq!(bundle.value, Some(b"value".to_vec()));
+    assert!(bundle.verify().is_ok());
+
+    // a valid proof with the header of another block
+    let other = Hash::repeat_byte(2);
+    assert!(StorageProofBundle::new_at(other, &header, b"key".to_vec(), proof).is_err());
+}
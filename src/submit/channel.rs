@@ -1,20 +1,20 @@
 use crate::deepsafe::runtime_types::pallet_channel::types::{
     CmtType, HandleConnection, TaprootType, TxSource, XudtStatus,
 };
-use crate::{handle_custom_error, DeepSafeSubClient};
+use crate::{handle_custom_error, DeepSafeSubClient, SubmitOptions, WaitFor};
 use sp_core::H256 as Hash;
 
 pub async fn create_channel(
     client: &DeepSafeSubClient,
     info: Vec<u8>,
     connections: Vec<HandleConnection>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .create_channel(info, connections);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -23,14 +23,14 @@ pub async fn bind_committees(
     client: &DeepSafeSubClient,
     channel_id: u32,
     connections: Vec<HandleConnection>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .bind_committees(channel_id, connections);
 
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -41,23 +41,15 @@ pub async fn submit_transaction(
     cid: u32,
     msg: Vec<u8>,
     source: TxSource,
-    need_watch_res: bool,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .import_new_tx(channel_id, cid, msg, source);
-    if need_watch_res {
-        client
-            .submit_extrinsic_with_signer_and_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    } else {
-        client
-            .submit_extrinsic_with_signer_without_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    }
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 pub async fn import_new_src_hash(
@@ -66,23 +58,15 @@ pub async fn import_new_src_hash(
     hash: Vec<u8>,
     src_chain_id: u32,
     uid: Vec<u8>,
-    need_watch_res: bool,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .import_new_source_hash(cid, hash, src_chain_id, uid);
-    if need_watch_res {
-        client
-            .submit_extrinsic_with_signer_and_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    } else {
-        client
-            .submit_extrinsic_with_signer_without_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    }
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 pub async fn report_result(
@@ -125,11 +109,11 @@ pub async fn request_sign(
     client: &DeepSafeSubClient,
     cid: u32,
     hash: Hash,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx().channel().request_sign(cid, hash);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -138,44 +122,28 @@ pub async fn sync_status(
     client: &DeepSafeSubClient,
     cid: u32,
     hash: Vec<u8>,
-    watch_res: bool,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx().channel().sync_status(cid, hash);
-    if watch_res {
-        client
-            .submit_extrinsic_with_signer_and_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    } else {
-        client
-            .submit_extrinsic_with_signer_without_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    }
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 pub async fn clear_target_package(
     client: &DeepSafeSubClient,
     cid: u32,
     package_key: Vec<u8>,
-    watch_res: bool,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .clear_target_package(cid, package_key);
-    if watch_res {
-        client
-            .submit_extrinsic_with_signer_and_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    } else {
-        client
-            .submit_extrinsic_with_signer_without_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    }
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 pub async fn create_channel_with_taproot(
@@ -183,7 +151,7 @@ pub async fn create_channel_with_taproot(
     info: Vec<u8>,
     connections: Vec<(u32, u32, Vec<u8>, CmtType)>,
     taproot_types: Vec<(u32, TaprootType)>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx().channel().create_channel_with_taproot(
         info,
@@ -191,7 +159,7 @@ pub async fn create_channel_with_taproot(
         taproot_types,
     );
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -202,8 +170,7 @@ pub async fn request_to_sign_refresh(
     inscription_tx: Vec<u8>,
     inscription_pos: u8,
     msg: Vec<u8>,
-    watch_res: bool,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx().channel().request_to_sign_refresh(
         cid,
@@ -211,17 +178,10 @@ pub async fn request_to_sign_refresh(
         inscription_pos,
         msg,
     );
-    if watch_res {
-        client
-            .submit_extrinsic_with_signer_and_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    } else {
-        client
-            .submit_extrinsic_with_signer_without_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    }
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 pub async fn request_to_sign_merge_tx(
@@ -229,23 +189,15 @@ pub async fn request_to_sign_merge_tx(
     cid: u32,
     record_hash: Vec<u8>,
     msg: Vec<u8>,
-    watch_res: bool,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .request_to_sign_merge_tx(cid, record_hash, msg);
-    if watch_res {
-        client
-            .submit_extrinsic_with_signer_and_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    } else {
-        client
-            .submit_extrinsic_with_signer_without_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    }
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 pub async fn submit_refresh_result(
@@ -349,13 +301,13 @@ pub async fn sign_issue_xudt(
     cid: u32,
     args_of_token: Vec<u8>,
     msg: Vec<u8>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .sign_issue_xudt(cid, args_of_token, msg);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -401,29 +353,34 @@ pub async fn sync_issue_xudt_result(
     cid: u32,
     args_of_token: Vec<u8>,
     status: XudtStatus,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .sync_issue_xudt_result(cid, args_of_token, status);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Fire-and-forget unless 'options.wait_for' is set.
 pub async fn update_src_hash_seq(
     client: &DeepSafeSubClient,
     cid: u32, // dst_cid
     src_chain: u32,
     src_hash: Vec<u8>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .update_src_hash_seq(cid, src_chain, src_hash);
+    let options = SubmitOptions {
+        wait_for: options.wait_for.or(Some(WaitFor::None)),
+        ..options
+    };
     client
-        .submit_extrinsic_with_signer_without_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -468,23 +425,15 @@ pub async fn request_to_sign_forced_withdrawal(
     client: &DeepSafeSubClient,
     tx_nonce: u128,
     msg: Vec<u8>,
-    watch_res: bool,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .channel()
         .sign_forced_withdrawal(tx_nonce, msg);
-    if watch_res {
-        client
-            .submit_extrinsic_with_signer_and_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    } else {
-        client
-            .submit_extrinsic_with_signer_without_watch(call, nonce)
-            .await
-            .map_err(|e| e.to_string())
-    }
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
 }
 pub async fn finish_forced_withdrawal_result(
     client: &DeepSafeSubClient,
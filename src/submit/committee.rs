@@ -1,6 +1,6 @@
 #![allow(clippy::too_many_arguments)]
 use crate::deepsafe::runtime_types::pallet_committee::types::CryptoType;
-use crate::{handle_custom_error, DeepSafeSubClient};
+use crate::{handle_custom_error, DeepSafeSubClient, SubmitOptions};
 use sp_core::H256 as Hash;
 
 pub async fn create_committee(
@@ -9,13 +9,13 @@ pub async fn create_committee(
     n: u16,
     crypto: CryptoType,
     fork: u8,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .committee()
         .create_committee(t, n, crypto, fork);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -81,13 +81,13 @@ pub async fn active_committee(
     cid: u32,
     chain_id: u32,
     address: Vec<u8>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx()
         .committee()
         .active_committee(cid, chain_id, address);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
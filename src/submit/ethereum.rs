@@ -1,4 +1,5 @@
 use crate::deepsafe::runtime_types::ethereum::transaction::TransactionV2 as Transaction;
+use crate::evm::receipt::{receipt_by_extrinsic, EvmReceipt};
 use crate::{handle_custom_error, DeepSafeSubClient};
 use sp_core::H256 as Hash;
 
@@ -38,3 +39,29 @@ pub async fn transact_unsigned_call_bytes(
         .await
         .map_err(handle_custom_error)
 }
+
+/// Submit 'transact_unsigned' and wait until it is in block, return the receipt of the evm tx.
+pub async fn transact_unsigned_and_wait_receipt(
+    client: &DeepSafeSubClient,
+    transaction: Transaction,
+) -> Result<EvmReceipt, String> {
+    let call = crate::deepsafe::tx()
+        .ethereum()
+        .transact_unsigned(transaction);
+    let in_block = client
+        .submit_extrinsic_without_signer_and_watch(call)
+        .await
+        .map_err(handle_custom_error)?
+        .wait_for_in_block()
+        .await
+        .map_err(handle_custom_error)?;
+    receipt_by_extrinsic(client, in_block.block_hash(), in_block.extrinsic_hash())
+        .await
+        .map_err(handle_custom_error)?
+        .ok_or_else(|| {
+            format!(
+                "no evm receipt of extrinsic {:?}",
+                in_block.extrinsic_hash()
+            )
+        })
+}
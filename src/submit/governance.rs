@@ -0,0 +1,469 @@
+#![allow(clippy::too_many_arguments)]
+use crate::deepsafe::runtime_types::bounded_collections::bounded_vec::BoundedVec;
+use crate::deepsafe::runtime_types::fp_account::AccountId20;
+use crate::deepsafe::runtime_types::frame_support::traits::{
+    preimages::Bounded, schedule::DispatchTime,
+};
+use crate::deepsafe::runtime_types::node_runtime::{OriginCaller, RuntimeCall};
+use crate::deepsafe::runtime_types::pallet_conviction_voting::{
+    conviction::Conviction,
+    vote::{AccountVote, Vote},
+};
+use crate::deepsafe::runtime_types::sp_runtime::{
+    multiaddress::MultiAddress, traits::BlakeTwo256, DispatchError as RuntimeDispatchError,
+};
+use crate::deepsafe::runtime_types::sp_weights::weight_v2::Weight;
+use crate::deepsafe::sudo::events::{Sudid, SudoAsDone};
+use crate::submit::utility::to_runtime_call;
+use crate::types::Collective;
+use crate::{DeepSafeSubClient, SubmitOptions};
+use codec::{Decode, Encode};
+use sp_core::H256 as Hash;
+use subxt::tx::TxPayload;
+
+/// Calls not longer than it are inlined in 'Bounded', longer ones need a noted preimage.
+pub const MAX_INLINE_CALL_LEN: usize = 128;
+
+pub type BoundedCall = Bounded<RuntimeCall, BlakeTwo256>;
+
+async fn runtime_call<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    call: Call,
+) -> Result<RuntimeCall, String> {
+    let metadata = client.client.read().await.metadata();
+    to_runtime_call(&call, &metadata)
+}
+
+/// Bound the call for referenda or scheduler, return the encoded call as well,
+/// it should be noted by 'note_preimage' first if the bounded call is 'Lookup'.
+pub async fn bounded_call<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    call: Call,
+) -> Result<(BoundedCall, Vec<u8>), String> {
+    let encoded = runtime_call(client, call).await?.encode();
+    let bounded = if encoded.len() <= MAX_INLINE_CALL_LEN {
+        Bounded::Inline(BoundedVec(encoded.clone()))
+    } else {
+        Bounded::Lookup {
+            hash: Hash(sp_core::blake2_256(&encoded)),
+            len: encoded.len() as u32,
+        }
+    };
+    Ok((bounded, encoded))
+}
+
+/// Vote with the conviction, aye is the highest bit of the vote byte.
+pub fn standard_vote(aye: bool, conviction: Conviction, balance: u128) -> AccountVote<u128> {
+    let conviction = conviction as u8;
+    AccountVote::Standard {
+        vote: Vote(if aye { 0x80 | conviction } else { conviction }),
+        balance,
+    }
+}
+
+/// Submit the sudo call and wait for it in block. The sudo extrinsic succeeds even if the
+/// wrapped call fails, so the error in 'Sudid' or 'SudoAsDone' is returned as well.
+async fn submit_sudo<Call: TxPayload + Send + Sync + 'static>(
+    client: &DeepSafeSubClient,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let events = client
+        .submit_extrinsic_with_signer_and_watch_events(call, options)
+        .await
+        .map_err(|e| e.to_string())?;
+    let sudo_result = match events.find_first::<Sudid>().map_err(|e| e.to_string())? {
+        Some(sudid) => Some(sudid.sudo_result),
+        None => events
+            .find_first::<SudoAsDone>()
+            .map_err(|e| e.to_string())?
+            .map(|done| done.sudo_result),
+    };
+    if let Some(Err(e)) = sudo_result {
+        let metadata = client.client.read().await.metadata();
+        return Err(format!(
+            "sudo call failed: {}",
+            sudo_error_name(&e, &metadata)
+        ));
+    }
+    Ok(events.extrinsic_hash())
+}
+
+// 'Pallet.Error' of the module error of the wrapped call
+fn sudo_error_name(err: &RuntimeDispatchError, metadata: &subxt::Metadata) -> String {
+    match sp_runtime::DispatchError::decode(&mut &err.encode()[..]) {
+        Ok(err) => crate::client::dispatch_error_name(&err, metadata),
+        Err(_) => format!("{err:?}"),
+    }
+}
+
+/// Dispatch 'call' by root, an error is returned if the wrapped call fails.
+pub async fn sudo<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .sudo()
+        .sudo(runtime_call(client, call).await?);
+    submit_sudo(client, call, options).await
+}
+
+pub async fn sudo_unchecked_weight<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    call: Call,
+    weight: Weight,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .sudo()
+        .sudo_unchecked_weight(runtime_call(client, call).await?, weight);
+    submit_sudo(client, call, options).await
+}
+
+pub async fn sudo_as<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    who: AccountId20,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .sudo()
+        .sudo_as(MultiAddress::Id(who), runtime_call(client, call).await?);
+    submit_sudo(client, call, options).await
+}
+
+pub async fn note_preimage(
+    client: &DeepSafeSubClient,
+    bytes: Vec<u8>,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().preimage().note_preimage(bytes);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn unnote_preimage(
+    client: &DeepSafeSubClient,
+    hash: Hash,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().preimage().unnote_preimage(hash);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Submit a referendum for 'call', the preimage is noted first if the call is too long
+/// to be inlined. Return the tx hash and the referendum index.
+pub async fn submit_referendum<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    proposal_origin: OriginCaller,
+    call: Call,
+    enactment_moment: DispatchTime<u32>,
+    options: SubmitOptions,
+) -> Result<(Hash, u32), String> {
+    let (proposal, encoded) = bounded_call(client, call).await?;
+    let mut options = options;
+    if let Bounded::Lookup { .. } = proposal {
+        note_preimage(client, encoded, options).await?;
+        options.nonce = options.nonce.map(|n| n + 1);
+    }
+    let call =
+        crate::deepsafe::tx()
+            .referenda()
+            .submit(proposal_origin, proposal, enactment_moment);
+    let events = client
+        .submit_extrinsic_with_signer_and_watch_events(call, options)
+        .await
+        .map_err(|e| e.to_string())?;
+    let submitted = events
+        .find_first::<crate::deepsafe::referenda::events::Submitted>()
+        .map_err(|e| e.to_string())?
+        .ok_or("no Submitted event for referendum")?;
+    Ok((events.extrinsic_hash(), submitted.index))
+}
+
+pub async fn place_decision_deposit(
+    client: &DeepSafeSubClient,
+    index: u32,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .referenda()
+        .place_decision_deposit(index);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn refund_decision_deposit(
+    client: &DeepSafeSubClient,
+    index: u32,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .referenda()
+        .refund_decision_deposit(index);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn vote(
+    client: &DeepSafeSubClient,
+    poll_index: u32,
+    vote: AccountVote<u128>,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .conviction_voting()
+        .vote(poll_index, vote);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn remove_vote(
+    client: &DeepSafeSubClient,
+    class: Option<u16>,
+    index: u32,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .conviction_voting()
+        .remove_vote(class, index);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn delegate(
+    client: &DeepSafeSubClient,
+    class: u16,
+    to: AccountId20,
+    conviction: Conviction,
+    balance: u128,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().conviction_voting().delegate(
+        class,
+        MultiAddress::Id(to),
+        conviction,
+        balance,
+    );
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn undelegate(
+    client: &DeepSafeSubClient,
+    class: u16,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().conviction_voting().undelegate(class);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn unlock(
+    client: &DeepSafeSubClient,
+    class: u16,
+    target: AccountId20,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .conviction_voting()
+        .unlock(class, MultiAddress::Id(target));
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn whitelist_call(
+    client: &DeepSafeSubClient,
+    call_hash: Hash,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().whitelist().whitelist_call(call_hash);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn dispatch_whitelisted_call_with_preimage<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .whitelist()
+        .dispatch_whitelisted_call_with_preimage(runtime_call(client, call).await?);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Propose 'call' to the collective, it's executed directly if 'threshold' is 1.
+pub async fn collective_propose<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    collective: Collective,
+    threshold: u32,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let proposal = runtime_call(client, call).await?;
+    let length_bound = proposal.encoded_size() as u32;
+    match collective {
+        Collective::TreasuryCouncil => {
+            let call = crate::deepsafe::tx().treasury_council_collective().propose(
+                threshold,
+                proposal,
+                length_bound,
+            );
+            client.submit_extrinsic_with_options(call, options).await
+        }
+        Collective::OpenTechCommittee => {
+            let call = crate::deepsafe::tx()
+                .open_tech_committee_collective()
+                .propose(threshold, proposal, length_bound);
+            client.submit_extrinsic_with_options(call, options).await
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+pub async fn collective_vote(
+    client: &DeepSafeSubClient,
+    collective: Collective,
+    proposal_hash: Hash,
+    index: u32,
+    approve: bool,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    match collective {
+        Collective::TreasuryCouncil => {
+            let call = crate::deepsafe::tx().treasury_council_collective().vote(
+                proposal_hash,
+                index,
+                approve,
+            );
+            client.submit_extrinsic_with_options(call, options).await
+        }
+        Collective::OpenTechCommittee => {
+            let call = crate::deepsafe::tx().open_tech_committee_collective().vote(
+                proposal_hash,
+                index,
+                approve,
+            );
+            client.submit_extrinsic_with_options(call, options).await
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+pub async fn collective_close(
+    client: &DeepSafeSubClient,
+    collective: Collective,
+    proposal_hash: Hash,
+    index: u32,
+    proposal_weight_bound: Weight,
+    length_bound: u32,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    match collective {
+        Collective::TreasuryCouncil => {
+            let call = crate::deepsafe::tx().treasury_council_collective().close(
+                proposal_hash,
+                index,
+                proposal_weight_bound,
+                length_bound,
+            );
+            client.submit_extrinsic_with_options(call, options).await
+        }
+        Collective::OpenTechCommittee => {
+            let call = crate::deepsafe::tx()
+                .open_tech_committee_collective()
+                .close(proposal_hash, index, proposal_weight_bound, length_bound);
+            client.submit_extrinsic_with_options(call, options).await
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Schedule 'call' at block 'when', the scheduler needs root origin so it's submitted by sudo.
+pub async fn schedule<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    when: u32,
+    maybe_periodic: Option<(u32, u32)>,
+    priority: u8,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().scheduler().schedule(
+        when,
+        maybe_periodic,
+        priority,
+        runtime_call(client, call).await?,
+    );
+    sudo(client, call, options).await
+}
+
+pub async fn schedule_named<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    id: [u8; 32],
+    when: u32,
+    maybe_periodic: Option<(u32, u32)>,
+    priority: u8,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().scheduler().schedule_named(
+        id,
+        when,
+        maybe_periodic,
+        priority,
+        runtime_call(client, call).await?,
+    );
+    sudo(client, call, options).await
+}
+
+pub async fn schedule_after<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    after: u32,
+    maybe_periodic: Option<(u32, u32)>,
+    priority: u8,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().scheduler().schedule_after(
+        after,
+        maybe_periodic,
+        priority,
+        runtime_call(client, call).await?,
+    );
+    sudo(client, call, options).await
+}
+
+pub async fn cancel_named(
+    client: &DeepSafeSubClient,
+    id: [u8; 32],
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().scheduler().cancel_named(id);
+    sudo(client, call, options).await
+}
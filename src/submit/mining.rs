@@ -1,5 +1,6 @@
 use crate::deepsafe::runtime_types::pallet_mining::types::{MonitorType, OnChainPayload};
-use crate::{handle_custom_error, DeepSafeSubClient};
+use crate::tx_tracker::call_names;
+use crate::{handle_custom_error, DeepSafeSubClient, SubmitOptions};
 use sp_core::H256 as Hash;
 
 pub async fn im_online(
@@ -46,11 +47,16 @@ pub async fn register_device_with_ident(
         monitor_type,
         signature,
     );
+    let names = call_names(&call);
     let tx_process = client
         .submit_extrinsic_without_signer_and_watch(call)
         .await
         .map_err(|e| e.to_string())?;
-    match tx_process.wait_for_finalized().await {
+    let finalized = match &client.tx_tracker {
+        Some(tracker) => tracker.track_and_wait(tx_process, None, names, true).await,
+        None => tx_process.wait_for_finalized().await,
+    };
+    match finalized {
         Ok(tx) => Ok(tx
             .wait_for_success()
             .await
@@ -63,11 +69,11 @@ pub async fn register_device_with_ident(
 pub async fn update_votes(
     client: &DeepSafeSubClient,
     changed_votes: Vec<(Vec<u8>, u128)>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx().mining().update_votes(changed_votes);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -75,11 +81,11 @@ pub async fn update_votes(
 pub async fn join_service(
     client: &DeepSafeSubClient,
     id: Vec<u8>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx().mining().join_service(id);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -87,11 +93,11 @@ pub async fn join_service(
 pub async fn exit_service(
     client: &DeepSafeSubClient,
     id: Vec<u8>,
-    nonce: Option<u32>,
+    options: SubmitOptions,
 ) -> Result<Hash, String> {
     let call = crate::deepsafe::tx().mining().exit_service(id);
     client
-        .submit_extrinsic_with_signer_and_watch(call, nonce)
+        .submit_extrinsic_with_options(call, options)
         .await
         .map_err(|e| e.to_string())
 }
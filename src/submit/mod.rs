@@ -3,5 +3,8 @@ pub mod committee;
 pub mod committee_assets;
 pub mod committee_health;
 pub mod ethereum;
+pub mod governance;
 pub mod mining;
+pub mod multisig;
 pub mod rpc;
+pub mod utility;
@@ -0,0 +1,278 @@
+#![allow(clippy::too_many_arguments)]
+use crate::deepsafe::runtime_types::fp_account::AccountId20;
+use crate::deepsafe::runtime_types::pallet_multisig::Timepoint;
+use crate::deepsafe::runtime_types::sp_weights::weight_v2::Weight;
+use crate::submit::utility::to_runtime_call;
+use crate::{DeepSafeSubClient, SubmitOptions};
+use codec::Encode;
+use serde::{Deserialize, Serialize};
+use sp_core::H256 as Hash;
+use subxt::tx::TxPayload;
+
+/// Derive the multisig account of 'signatories' with 'threshold', the same as
+/// 'pallet_multisig::Pallet::multi_account_id', signatories needn't to be sorted.
+pub fn multi_account_id(signatories: &[AccountId20], threshold: u16) -> AccountId20 {
+    let mut who = signatories.iter().map(|s| s.0).collect::<Vec<_>>();
+    who.sort();
+    let entropy = (b"modlpy/utilisuba", who, threshold).using_encoded(sp_core::blake2_256);
+    let mut account = [0u8; 20];
+    account.copy_from_slice(&entropy[..20]);
+    AccountId20(account)
+}
+
+/// Signatories except 'me', sorted as required by the multisig calls.
+pub fn other_signatories(signatories: &[AccountId20], me: &AccountId20) -> Vec<AccountId20> {
+    let mut others = signatories
+        .iter()
+        .filter(|s| *s != me)
+        .cloned()
+        .collect::<Vec<_>>();
+    others.sort_by_key(|s| s.0);
+    others.dedup();
+    others
+}
+
+/// The call data and hash of a multisig operation, could be exported as json
+/// so that other signatories could review it before approving.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigCallExport {
+    // hex of the multisig account
+    pub multisig: String,
+    pub threshold: u16,
+    // hex of all signatories, sorted
+    pub signatories: Vec<String>,
+    pub pallet: String,
+    pub call: String,
+    // hex of the SCALE encoded 'RuntimeCall'
+    pub call_data: String,
+    // hex of 'blake2_256(call_data)'
+    pub call_hash: String,
+    // '(height, index)' of the first approval, 'None' for a new operation
+    pub timepoint: Option<(u32, u32)>,
+}
+
+impl MultisigCallExport {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    pub fn call_data_bytes(&self) -> Result<Vec<u8>, String> {
+        hex::decode(crate::no_prefix(&self.call_data)).map_err(|e| e.to_string())
+    }
+
+    pub fn call_hash_bytes(&self) -> Result<[u8; 32], String> {
+        let bytes = hex::decode(crate::no_prefix(&self.call_hash)).map_err(|e| e.to_string())?;
+        bytes
+            .try_into()
+            .map_err(|_| "invalid call hash length".to_string())
+    }
+}
+
+pub async fn export_multisig_call<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    signatories: Vec<AccountId20>,
+    threshold: u16,
+    call: Call,
+    timepoint: Option<Timepoint<u32>>,
+) -> Result<MultisigCallExport, String> {
+    let metadata = client.client.read().await.metadata();
+    let call_data = to_runtime_call(&call, &metadata)?.encode();
+    let (pallet, call_name) = metadata
+        .pallet_by_index(call_data[0])
+        .and_then(|pallet| {
+            pallet
+                .call_variant_by_index(call_data[1])
+                .map(|call| (pallet.name().to_string(), call.name.clone()))
+        })
+        .ok_or("call not found in metadata")?;
+    let mut signatories = signatories;
+    signatories.sort_by_key(|s| s.0);
+    signatories.dedup();
+    let multisig = multi_account_id(&signatories, threshold);
+    Ok(MultisigCallExport {
+        multisig: format!("0x{}", hex::encode(multisig.0)),
+        threshold,
+        signatories: signatories
+            .iter()
+            .map(|s| format!("0x{}", hex::encode(s.0)))
+            .collect(),
+        pallet,
+        call: call_name,
+        call_hash: format!("0x{}", hex::encode(sp_core::blake2_256(&call_data))),
+        call_data: format!("0x{}", hex::encode(call_data)),
+        timepoint: timepoint.map(|t| (t.height, t.index)),
+    })
+}
+
+/// Approve and execute 'call' by the multisig, the call is executed once 'threshold' approvals reached.
+pub async fn as_multi<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    threshold: u16,
+    other_signatories: Vec<AccountId20>,
+    maybe_timepoint: Option<Timepoint<u32>>,
+    call: Call,
+    max_weight: Weight,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let metadata = client.client.read().await.metadata();
+    let call = to_runtime_call(&call, &metadata)?;
+    let call = crate::deepsafe::tx().multisig().as_multi(
+        threshold,
+        other_signatories,
+        maybe_timepoint,
+        call,
+        max_weight,
+    );
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn as_multi_threshold_1<Call: TxPayload>(
+    client: &DeepSafeSubClient,
+    other_signatories: Vec<AccountId20>,
+    call: Call,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let metadata = client.client.read().await.metadata();
+    let call = to_runtime_call(&call, &metadata)?;
+    let call = crate::deepsafe::tx()
+        .multisig()
+        .as_multi_threshold_1(other_signatories, call);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn approve_as_multi(
+    client: &DeepSafeSubClient,
+    threshold: u16,
+    other_signatories: Vec<AccountId20>,
+    maybe_timepoint: Option<Timepoint<u32>>,
+    call_hash: [u8; 32],
+    max_weight: Weight,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().multisig().approve_as_multi(
+        threshold,
+        other_signatories,
+        maybe_timepoint,
+        call_hash,
+        max_weight,
+    );
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn cancel_as_multi(
+    client: &DeepSafeSubClient,
+    threshold: u16,
+    other_signatories: Vec<AccountId20>,
+    timepoint: Timepoint<u32>,
+    call_hash: [u8; 32],
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx().multisig().cancel_as_multi(
+        threshold,
+        other_signatories,
+        timepoint,
+        call_hash,
+    );
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn register_multi_account(
+    client: &DeepSafeSubClient,
+    threshold: u16,
+    other_signatories: Vec<AccountId20>,
+    options: SubmitOptions,
+) -> Result<Hash, String> {
+    let call = crate::deepsafe::tx()
+        .multisig()
+        .register_multi_account(threshold, other_signatories);
+    client
+        .submit_extrinsic_with_options(call, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_multi_account_id_ignores_order() {
+    let (a, b, c) = (
+        AccountId20([1u8; 20]),
+        AccountId20([2u8; 20]),
+        AccountId20([3u8; 20]),
+    );
+    assert_eq!(
+        multi_account_id(&[a.clone(), b.clone(), c.clone()], 2),
+        multi_account_id(&[c.clone(), a.clone(), b.clone()], 2)
+    );
+    assert_ne!(
+        multi_account_id(&[a.clone(), b.clone(), c.clone()], 2),
+        multi_account_id(&[a.clone(), b.clone(), c.clone()], 3)
+    );
+    assert_eq!(other_signatories(&[c.clone(), a.clone(), b], &a)[1], c);
+}
+
+#[test]
+fn test_multi_account_id_known_address() {
+    // alith, baltathar and charleth of the dev chain
+    let signatories = [
+        "f24ff3a9cf04c71dbc94d0b566f7a27b94566cac",
+        "3cd0a705a2dc65e5b1e1205896baa2be8a07c6e0",
+        "798d4ba9baf0064ec19eb4f0a1a45785ae9d6dfc",
+    ]
+    .iter()
+    .map(|s| AccountId20(hex::decode(s).unwrap().try_into().unwrap()))
+    .collect::<Vec<_>>();
+    assert_eq!(
+        hex::encode(multi_account_id(&signatories, 2).0),
+        "7a1671a0224c8927b08f978027d586ab6868de0d"
+    );
+}
+
+#[tokio::test]
+async fn test_multi_account_id_by_runtime() {
+    let url = "ws://127.0.0.1:9944".to_string();
+    let sk = "5fb92d6e98884f76de468fa3f6278f8807c48bebc13595d45af5bdc4da702133".to_string(); // alith
+    let client = crate::client::SubClient::new_from_ecdsa_sk(url, Some(sk), None, None)
+        .await
+        .unwrap();
+    let others = [
+        "3cd0a705a2dc65e5b1e1205896baa2be8a07c6e0",
+        "798d4ba9baf0064ec19eb4f0a1a45785ae9d6dfc",
+    ]
+    .iter()
+    .map(|s| AccountId20(hex::decode(s).unwrap().try_into().unwrap()))
+    .collect::<Vec<_>>();
+    let call = crate::deepsafe::tx()
+        .multisig()
+        .register_multi_account(2, others.clone());
+    let events = client
+        .submit_extrinsic_with_signer_and_watch_events(call, SubmitOptions::default())
+        .await
+        .unwrap();
+    let created = events
+        .find_first::<crate::deepsafe::multisig::events::MultiAccountCreated>()
+        .unwrap()
+        .unwrap();
+    let mut signatories = others;
+    signatories.push(AccountId20(
+        hex::decode("f24ff3a9cf04c71dbc94d0b566f7a27b94566cac")
+            .unwrap()
+            .try_into()
+            .unwrap(),
+    ));
+    assert_eq!(created.multisig, multi_account_id(&signatories, 2));
+}
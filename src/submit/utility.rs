@@ -0,0 +1,184 @@
+use crate::deepsafe::runtime_types::node_runtime::RuntimeCall;
+use crate::deepsafe::utility::events::{BatchInterrupted, ItemCompleted, ItemFailed};
+use crate::{DeepSafeSubClient, SubmitOptions};
+use codec::Decode;
+use sp_core::H256 as Hash;
+use subxt::tx::TxPayload;
+use subxt::Metadata;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BatchMode {
+    // stop at the first failed call, calls before it are kept
+    Batch,
+    // revert all calls if any one failed
+    #[default]
+    BatchAll,
+    // continue when a call failed
+    ForceBatch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchItemResult {
+    Completed,
+    // debug string of the dispatch error
+    Failed(String),
+    // not dispatched because the batch is interrupted before it
+    NotExecuted,
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchResult {
+    pub tx_hash: Hash,
+    pub block_hash: Hash,
+    // one result for every call, in the pushed order
+    pub items: Vec<BatchItemResult>,
+}
+
+impl BatchResult {
+    pub fn is_all_completed(&self) -> bool {
+        self.items
+            .iter()
+            .all(|item| *item == BatchItemResult::Completed)
+    }
+}
+
+/// Convert any static call, i.e. 'crate::deepsafe::tx().mining().update_votes(..)',
+/// into 'RuntimeCall' which could be wrapped by batch, multisig or sudo calls.
+pub fn to_runtime_call<Call: TxPayload>(
+    call: &Call,
+    metadata: &Metadata,
+) -> Result<RuntimeCall, String> {
+    let call_data = call.encode_call_data(metadata).map_err(|e| e.to_string())?;
+    RuntimeCall::decode(&mut call_data.as_slice()).map_err(|e| e.to_string())
+}
+
+/// Collect calls and submit them in one 'Utility' batch extrinsic.
+#[derive(Clone, Debug, Default)]
+pub struct BatchBuilder {
+    pub mode: BatchMode,
+    pub calls: Vec<RuntimeCall>,
+}
+
+impl BatchBuilder {
+    pub fn new(mode: BatchMode) -> Self {
+        BatchBuilder {
+            mode,
+            calls: Vec::new(),
+        }
+    }
+
+    pub async fn push<Call: TxPayload>(
+        &mut self,
+        client: &DeepSafeSubClient,
+        call: Call,
+    ) -> Result<&mut Self, String> {
+        let metadata = client.client.read().await.metadata();
+        self.calls.push(to_runtime_call(&call, &metadata)?);
+        Ok(self)
+    }
+
+    pub fn push_runtime_call(&mut self, call: RuntimeCall) -> &mut Self {
+        self.calls.push(call);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Submit the batch and wait it in block (or finalized by 'options.wait_for'), then decode
+    /// the result of every call from 'ItemCompleted', 'ItemFailed' and 'BatchInterrupted' events.
+    pub async fn submit(
+        self,
+        client: &DeepSafeSubClient,
+        options: SubmitOptions,
+    ) -> Result<BatchResult, String> {
+        if self.calls.is_empty() {
+            return Err("empty batch calls".to_string());
+        }
+        let limit = client
+            .query_constant(crate::deepsafe::constants().utility().batched_calls_limit())
+            .await
+            .map_err(|e| e.to_string())?;
+        if self.calls.len() > limit as usize {
+            return Err(format!(
+                "batch calls {} exceed limit {}",
+                self.calls.len(),
+                limit
+            ));
+        }
+        let calls_len = self.calls.len();
+        let utility = crate::deepsafe::tx().utility();
+        let events = match self.mode {
+            BatchMode::Batch => {
+                client
+                    .submit_extrinsic_with_signer_and_watch_events(
+                        utility.batch(self.calls),
+                        options,
+                    )
+                    .await
+            }
+            BatchMode::BatchAll => {
+                client
+                    .submit_extrinsic_with_signer_and_watch_events(
+                        utility.batch_all(self.calls),
+                        options,
+                    )
+                    .await
+            }
+            BatchMode::ForceBatch => {
+                client
+                    .submit_extrinsic_with_signer_and_watch_events(
+                        utility.force_batch(self.calls),
+                        options,
+                    )
+                    .await
+            }
+        }
+        .map_err(|e| e.to_string())?;
+
+        let mut items = Vec::with_capacity(calls_len);
+        for event in events.iter() {
+            let event = event.map_err(|e| e.to_string())?;
+            if event
+                .as_event::<ItemCompleted>()
+                .map_err(|e| e.to_string())?
+                .is_some()
+            {
+                items.push(BatchItemResult::Completed);
+            } else if let Some(failed) =
+                event.as_event::<ItemFailed>().map_err(|e| e.to_string())?
+            {
+                items.push(BatchItemResult::Failed(format!("{:?}", failed.error)));
+            } else if let Some(interrupted) = event
+                .as_event::<BatchInterrupted>()
+                .map_err(|e| e.to_string())?
+            {
+                items.truncate(interrupted.index as usize);
+                items.push(BatchItemResult::Failed(format!("{:?}", interrupted.error)));
+            }
+        }
+        items.resize(calls_len, BatchItemResult::NotExecuted);
+        Ok(BatchResult {
+            tx_hash: events.extrinsic_hash(),
+            block_hash: events.block_hash(),
+            items,
+        })
+    }
+}
+
+#[test]
+fn test_to_runtime_call() {
+    use crate::deepsafe::runtime_types::pallet_mining::pallet::Call as MiningCall;
+
+    let metadata = Metadata::decode(&mut &crate::compatibility::COMPILED_METADATA[..]).unwrap();
+    let call = crate::deepsafe::tx().mining().join_service(vec![1, 2, 3]);
+    match to_runtime_call(&call, &metadata).unwrap() {
+        RuntimeCall::Mining(MiningCall::join_service { id }) => assert_eq!(id, vec![1, 2, 3]),
+        other => panic!("unexpected call: {other:?}"),
+    }
+}
@@ -0,0 +1,440 @@
+//! Lifecycle tracking of submitted extrinsics, from broadcast until finalized or dropped.
+use crate::DeepSafeConfig;
+use sp_core::H256 as Hash;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use subxt::tx::{TxInBlock, TxPayload, TxProgress, TxStatus as SubxtTxStatus};
+use subxt::{Error, OnlineClient};
+use tokio::sync::{oneshot, watch, RwLock};
+
+type Progress = TxProgress<DeepSafeConfig, OnlineClient<DeepSafeConfig>>;
+type InBlock = TxInBlock<DeepSafeConfig, OnlineClient<DeepSafeConfig>>;
+type Waiter = oneshot::Sender<Result<InBlock, Error>>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Submitted to the node, no status reported yet.
+    Submitted,
+    /// In the tx pool of the node, maybe broadcast to peers.
+    Broadcast,
+    /// Included in the block, could be retracted by re-org.
+    InBlock(Hash),
+    /// The block including the tx is retracted, the tx is back in the pool.
+    Retracted(Hash),
+    Finalized(Hash),
+    /// The tx is still in block but not finalized in time, the node stops watching it.
+    FinalityTimeout(Hash),
+    /// Replaced by another tx with the same nonce, the hash of the new tx.
+    Usurped(Hash),
+    Dropped,
+    Invalid,
+    /// The status subscription is lost before a final status.
+    Unknown(String),
+}
+
+impl TxStatus {
+    /// No more status will be reported after a final one.
+    pub fn is_final(&self) -> bool {
+        !matches!(
+            self,
+            TxStatus::Submitted
+                | TxStatus::Broadcast
+                | TxStatus::InBlock(_)
+                | TxStatus::Retracted(_)
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrackedTx {
+    pub hash: Hash,
+    // 'None' for unsigned tx
+    pub nonce: Option<u32>,
+    pub pallet: String,
+    pub call: String,
+    pub submitted_at: SystemTime,
+    pub status: TxStatus,
+}
+
+/// Default of how long a tx with a final status is kept by 'TxTracker'.
+pub const TRACKED_TX_MAX_AGE: Duration = Duration::from_secs(3600);
+
+/// Default of the number of tx kept by 'TxTracker'.
+pub const TRACKED_TX_CAPACITY: usize = 10_000;
+
+#[derive(Debug)]
+struct TrackedEntry {
+    tx: TrackedTx,
+    sender: watch::Sender<TxStatus>,
+    // when the final status is reported
+    finished_at: Option<SystemTime>,
+}
+
+/// Records submitted extrinsics and follows the status of each one in the background.
+///
+/// Set it to SubClient by 'set_tx_tracker', then every tx submitted by the client is
+/// tracked no matter how long the submission waits. A finished tx is pruned 'max_age' after
+/// its final status, and the oldest tx are pruned once 'capacity' tx are tracked.
+#[derive(Clone, Debug)]
+pub struct TxTracker {
+    entries: Arc<RwLock<HashMap<Hash, TrackedEntry>>>,
+    max_age: Duration,
+    capacity: usize,
+}
+
+impl Default for TxTracker {
+    fn default() -> Self {
+        Self::with_limits(TRACKED_TX_MAX_AGE, TRACKED_TX_CAPACITY)
+    }
+}
+
+impl TxTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limits(max_age: Duration, capacity: usize) -> Self {
+        Self {
+            entries: Default::default(),
+            max_age,
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record the tx of 'progress' and follow its status in the background.
+    pub async fn track(&self, progress: Progress, nonce: Option<u32>, call: (String, String)) {
+        self.start(progress, nonce, call, None).await;
+    }
+
+    /// Same as 'track', but return once the tx is in block, or finalized if 'finalized',
+    /// the status is still followed in the background after returned.
+    pub async fn track_and_wait(
+        &self,
+        progress: Progress,
+        nonce: Option<u32>,
+        call: (String, String),
+        finalized: bool,
+    ) -> Result<InBlock, Error> {
+        let hash = progress.extrinsic_hash();
+        let (waiter, waited) = oneshot::channel();
+        self.start(progress, nonce, call, Some((waiter, finalized)))
+            .await;
+        waited
+            .await
+            .map_err(|_| Error::Other(format!("tracking of tx {hash:?} is aborted")))?
+    }
+
+    /// Record a tx whose status is followed by the submitter instead of the tracker, i.e. the
+    /// progress is returned to the caller, the status is reported by 'set_status'. A tx
+    /// recorded already keeps its status and waiters, only the known fields are updated.
+    pub async fn record(&self, hash: Hash, nonce: Option<u32>, (pallet, call): (String, String)) {
+        let mut entries = self.entries.write().await;
+        if let Some(entry) = entries.get_mut(&hash) {
+            if nonce.is_some() {
+                entry.tx.nonce = nonce;
+            }
+            if !pallet.is_empty() {
+                entry.tx.pallet = pallet;
+                entry.tx.call = call;
+            }
+            return;
+        }
+        let (sender, _) = watch::channel(TxStatus::Submitted);
+        prune(
+            &mut entries,
+            SystemTime::now(),
+            self.max_age,
+            self.capacity - 1,
+        );
+        entries.insert(
+            hash,
+            TrackedEntry {
+                tx: TrackedTx {
+                    hash,
+                    nonce,
+                    pallet,
+                    call,
+                    submitted_at: SystemTime::now(),
+                    status: TxStatus::Submitted,
+                },
+                sender,
+                finished_at: None,
+            },
+        );
+    }
+
+    async fn start(
+        &self,
+        progress: Progress,
+        nonce: Option<u32>,
+        call: (String, String),
+        waiter: Option<(Waiter, bool)>,
+    ) {
+        let hash = progress.extrinsic_hash();
+        self.record(hash, nonce, call).await;
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            tracker.follow(hash, progress, waiter).await;
+        });
+    }
+
+    async fn follow(&self, hash: Hash, mut progress: Progress, mut waiter: Option<(Waiter, bool)>) {
+        loop {
+            let status = match progress.next_item().await {
+                Some(Ok(status)) => status,
+                Some(Err(e)) => {
+                    self.set_status(hash, TxStatus::Unknown(e.to_string()))
+                        .await;
+                    if let Some((waiter, _)) = waiter.take() {
+                        let _ = waiter.send(Err(e));
+                    }
+                    return;
+                }
+                None => {
+                    self.set_status(hash, TxStatus::Unknown("subscription ended".to_string()))
+                        .await;
+                    if let Some((waiter, _)) = waiter.take() {
+                        let _ = waiter.send(Err(Error::Other(format!(
+                            "status subscription of tx {hash:?} ended"
+                        ))));
+                    }
+                    return;
+                }
+            };
+            let (status, in_block) = match status {
+                SubxtTxStatus::Future | SubxtTxStatus::Ready => (TxStatus::Submitted, None),
+                SubxtTxStatus::Broadcast(_) => (TxStatus::Broadcast, None),
+                SubxtTxStatus::InBlock(in_block) => {
+                    (TxStatus::InBlock(in_block.block_hash()), Some(in_block))
+                }
+                SubxtTxStatus::Retracted(block) => (TxStatus::Retracted(block), None),
+                SubxtTxStatus::FinalityTimeout(block) => (TxStatus::FinalityTimeout(block), None),
+                SubxtTxStatus::Finalized(in_block) => {
+                    (TxStatus::Finalized(in_block.block_hash()), Some(in_block))
+                }
+                SubxtTxStatus::Usurped(by) => (TxStatus::Usurped(by), None),
+                SubxtTxStatus::Dropped => (TxStatus::Dropped, None),
+                SubxtTxStatus::Invalid => (TxStatus::Invalid, None),
+            };
+            self.set_status(hash, status.clone()).await;
+            if let Some(in_block) = in_block {
+                let reached = match &waiter {
+                    Some((_, finalized)) => !finalized || matches!(status, TxStatus::Finalized(_)),
+                    None => false,
+                };
+                if reached {
+                    if let Some((waiter, _)) = waiter.take() {
+                        let _ = waiter.send(Ok(in_block));
+                    }
+                }
+            }
+            if status.is_final() {
+                if let Some((waiter, _)) = waiter.take() {
+                    let _ = waiter.send(Err(Error::Other(format!(
+                        "tx {hash:?} is not included: {status:?}"
+                    ))));
+                }
+                return;
+            }
+        }
+    }
+
+    pub async fn set_status(&self, hash: Hash, status: TxStatus) {
+        if let Some(entry) = self.entries.write().await.get_mut(&hash) {
+            log::debug!(target: "subxt::tracker", "tx {:?} status: {:?}", hash, status);
+            if status.is_final() && entry.finished_at.is_none() {
+                entry.finished_at = Some(SystemTime::now());
+            }
+            entry.tx.status = status.clone();
+            entry.sender.send_replace(status);
+        }
+    }
+
+    pub async fn status(&self, hash: &Hash) -> Option<TxStatus> {
+        self.entries
+            .read()
+            .await
+            .get(hash)
+            .map(|entry| entry.tx.status.clone())
+    }
+
+    pub async fn get(&self, hash: &Hash) -> Option<TrackedTx> {
+        self.entries
+            .read()
+            .await
+            .get(hash)
+            .map(|entry| entry.tx.clone())
+    }
+
+    /// All tracked tx without a final status.
+    pub async fn pending(&self) -> Vec<TrackedTx> {
+        self.entries
+            .read()
+            .await
+            .values()
+            .filter(|entry| !entry.tx.status.is_final())
+            .map(|entry| entry.tx.clone())
+            .collect()
+    }
+
+    /// Wait until the tx reaches a final status, 'None' if the tx isn't tracked or is removed
+    /// before a final status.
+    pub async fn wait_for_completion(&self, hash: &Hash) -> Option<TxStatus> {
+        let mut receiver = self.entries.read().await.get(hash)?.sender.subscribe();
+        loop {
+            let status = receiver.borrow_and_update().clone();
+            if status.is_final() {
+                return Some(status);
+            }
+            // the entry is removed while waiting
+            if receiver.changed().await.is_err() {
+                return None;
+            }
+        }
+    }
+
+    pub async fn remove(&self, hash: &Hash) -> Option<TrackedTx> {
+        self.entries
+            .write()
+            .await
+            .remove(hash)
+            .map(|entry| entry.tx)
+    }
+
+    /// Remove all tx with a final status, return the number of removed.
+    pub async fn prune_finished(&self) -> usize {
+        let mut entries = self.entries.write().await;
+        let before = entries.len();
+        entries.retain(|_, entry| !entry.tx.status.is_final());
+        before - entries.len()
+    }
+}
+
+/// Remove the finished tx older than 'max_age', then the oldest tx, finished first, until at
+/// most 'capacity' tx are left.
+fn prune(
+    entries: &mut HashMap<Hash, TrackedEntry>,
+    now: SystemTime,
+    max_age: Duration,
+    capacity: usize,
+) {
+    entries.retain(|_, entry| match entry.finished_at {
+        Some(finished_at) => now.duration_since(finished_at).unwrap_or_default() < max_age,
+        None => true,
+    });
+    if entries.len() <= capacity {
+        return;
+    }
+    let mut oldest = entries
+        .values()
+        .map(|entry| {
+            (
+                entry.finished_at.is_none(),
+                entry.tx.submitted_at,
+                entry.tx.hash,
+            )
+        })
+        .collect::<Vec<_>>();
+    oldest.sort();
+    for (_, _, hash) in oldest.into_iter().take(entries.len() - capacity) {
+        entries.remove(&hash);
+    }
+}
+
+/// '(pallet, call)' names of the static call, empty for dynamic call.
+pub fn call_names<Call: TxPayload>(call: &Call) -> (String, String) {
+    call.validation_details()
+        .map(|details| {
+            (
+                details.pallet_name.to_string(),
+                details.call_name.to_string(),
+            )
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_tx_status_is_final() {
+    assert!(!TxStatus::Submitted.is_final());
+    assert!(!TxStatus::InBlock(Hash::zero()).is_final());
+    assert!(!TxStatus::Retracted(Hash::zero()).is_final());
+    assert!(TxStatus::Finalized(Hash::zero()).is_final());
+    assert!(TxStatus::Usurped(Hash::zero()).is_final());
+    assert!(TxStatus::Dropped.is_final());
+}
+
+#[test]
+fn test_prune_tracked_tx() {
+    let now = SystemTime::now();
+    let entry = |byte: u8, submitted_secs: u64, finished_secs: Option<u64>| {
+        let hash = Hash::repeat_byte(byte);
+        let entry = TrackedEntry {
+            tx: TrackedTx {
+                hash,
+                nonce: Some(byte as u32),
+                pallet: "Channel".to_string(),
+                call: "sync_status".to_string(),
+                submitted_at: now - Duration::from_secs(submitted_secs),
+                status: TxStatus::Submitted,
+            },
+            sender: watch::channel(TxStatus::Submitted).0,
+            finished_at: finished_secs.map(|secs| now - Duration::from_secs(secs)),
+        };
+        (hash, entry)
+    };
+    let mut entries = HashMap::from([
+        entry(1, 100, Some(90)),
+        entry(2, 50, Some(40)),
+        entry(3, 200, None),
+        entry(4, 10, None),
+    ]);
+    // finished too long ago
+    prune(&mut entries, now, Duration::from_secs(60), 10);
+    assert_eq!(entries.len(), 3);
+    assert!(!entries.contains_key(&Hash::repeat_byte(1)));
+    // finished tx go first, then the oldest pending tx
+    prune(&mut entries, now, Duration::from_secs(60), 2);
+    assert!(!entries.contains_key(&Hash::repeat_byte(2)));
+    prune(&mut entries, now, Duration::from_secs(60), 1);
+    assert!(entries.contains_key(&Hash::repeat_byte(4)));
+    assert_eq!(entries.len(), 1);
+}
+
+#[tokio::test]
+async fn test_record_tracked_tx() {
+    let tracker = TxTracker::new();
+    let hash = Hash::repeat_byte(1);
+    tracker.record(hash, None, Default::default()).await;
+    let mut receiver = tracker
+        .entries
+        .read()
+        .await
+        .get(&hash)
+        .unwrap()
+        .sender
+        .subscribe();
+    // recorded again by the tracking of the same tx, the waiters are kept
+    tracker
+        .record(hash, None, ("Mining".to_string(), "heartbeat".to_string()))
+        .await;
+    assert_eq!(tracker.get(&hash).await.unwrap().pallet, "Mining");
+    tracker.set_status(hash, TxStatus::Dropped).await;
+    assert!(receiver.changed().await.is_ok());
+    assert_eq!(*receiver.borrow(), TxStatus::Dropped);
+    assert_eq!(
+        tracker.wait_for_completion(&hash).await,
+        Some(TxStatus::Dropped)
+    );
+
+    // removed while waiting
+    let hash = Hash::repeat_byte(2);
+    tracker.record(hash, None, Default::default()).await;
+    let waiting = {
+        let tracker = tracker.clone();
+        tokio::spawn(async move { tracker.wait_for_completion(&hash).await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    tracker.remove(&hash).await;
+    assert_eq!(waiting.await.unwrap(), None);
+}
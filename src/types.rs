@@ -1,5 +1,11 @@
+use codec::{Decode, Encode};
 use def_chain_bridge::chain::ChainType;
 use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256, U256};
+use sp_runtime::transaction_validity::{
+    InvalidTransaction, TransactionValidity, TransactionValidityError, UnknownTransaction,
+    ValidTransaction,
+};
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct SyncStatus {
@@ -33,3 +39,131 @@ pub struct NeedSignedExtrinsic {
     pub id: u32,
     pub data: ExtrinsicData,
 }
+
+/// Instances of 'pallet_collective' in the runtime.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Collective {
+    TreasuryCouncil,
+    OpenTechCommittee,
+}
+
+/// Result of runtime api 'TransactionPaymentApi_query_info'.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct RuntimeDispatchInfo {
+    pub weight: crate::deepsafe::runtime_types::sp_weights::weight_v2::Weight,
+    pub class: crate::deepsafe::runtime_types::frame_support::dispatch::DispatchClass,
+    // the inclusion fee without tip
+    pub partial_fee: u128,
+}
+
+/// Reason of a tx rejected by the runtime validation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidTx {
+    pub error: TransactionValidityError,
+    // decoded 'CustomError' of the runtime if the error is 'Custom(code)'
+    pub custom_error: Option<String>,
+}
+
+impl From<TransactionValidityError> for InvalidTx {
+    fn from(error: TransactionValidityError) -> Self {
+        let custom_error = match error {
+            TransactionValidityError::Invalid(InvalidTransaction::Custom(code))
+            | TransactionValidityError::Unknown(UnknownTransaction::Custom(code)) => {
+                Some(crate::convert_to_custom_error(code))
+            }
+            _ => None,
+        };
+        InvalidTx {
+            error,
+            custom_error,
+        }
+    }
+}
+
+/// Result of runtime api 'TaggedTransactionQueue_validate_transaction'.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxValidity {
+    /// Priority, longevity and tags of the tx in the tx pool.
+    Valid(ValidTransaction),
+    Invalid(InvalidTx),
+}
+
+impl From<TransactionValidity> for TxValidity {
+    fn from(validity: TransactionValidity) -> Self {
+        match validity {
+            Ok(valid) => TxValidity::Valid(valid),
+            Err(e) => TxValidity::Invalid(e.into()),
+        }
+    }
+}
+
+/// Result of dry running a signed tx on the latest block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DryRunOutcome {
+    Success(ValidTransaction),
+    /// The tx is valid but the call failed, with the name of the dispatch error, i.e. 'Committee.NotExist'.
+    DispatchFailed(ValidTransaction, String),
+    /// The tx would be rejected by the tx pool.
+    Invalid(InvalidTx),
+}
+
+/// Request of runtime api 'EthereumRuntimeRPCApi_call', or 'EthereumRuntimeRPCApi_create' if
+/// 'to' is 'None'.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvmCallRequest {
+    pub from: H160,
+    // 'None' to create a contract with 'data' as the init code
+    pub to: Option<H160>,
+    pub data: Vec<u8>,
+    pub value: U256,
+    // the gas limit of the current block is used if 'None'
+    pub gas_limit: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub nonce: Option<U256>,
+    pub access_list: Option<Vec<(H160, Vec<H256>)>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct EvmUsedGas {
+    // gas used by the evm execution
+    pub standard: U256,
+    // max of 'standard' and the gas converted from the used weight and proof size
+    pub effective: U256,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct EvmWeightInfo {
+    pub ref_time_limit: Option<u64>,
+    pub proof_size_limit: Option<u64>,
+    pub ref_time_usage: Option<u64>,
+    pub proof_size_usage: Option<u64>,
+}
+
+/// 'ExecutionInfoV2' returned by 'EthereumRuntimeRPCApi' version 5, 'value' is the returned data
+/// of a call or the address of a created contract.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct EvmExecutionInfo<T> {
+    pub exit_reason: crate::deepsafe::runtime_types::evm_core::error::ExitReason,
+    pub value: T,
+    pub used_gas: EvmUsedGas,
+    pub weight_info: Option<EvmWeightInfo>,
+    pub logs: Vec<crate::deepsafe::runtime_types::ethereum::log::Log>,
+}
+
+impl<T> EvmExecutionInfo<T> {
+    pub fn is_succeed(&self) -> bool {
+        matches!(
+            self.exit_reason,
+            crate::deepsafe::runtime_types::evm_core::error::ExitReason::Succeed(_)
+        )
+    }
+}
+
+/// Result of runtime api 'EthereumRuntimeRPCApi_account_basic'.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct EvmAccount {
+    // same as the system nonce of the account, shared by substrate and evm tx
+    pub nonce: U256,
+    pub balance: U256,
+}
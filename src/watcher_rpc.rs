@@ -6,23 +6,24 @@ use crate::deepsafe::runtime_types::{
     pallet_facility::pallet::DIdentity,
     pallet_mining::types::{MonitorType, OnChainPayload, Purpose},
 };
+use crate::evm::fee::apply_client_fees;
 use crate::no_prefix;
+use crate::precompile::{JOIN_OR_EXIT_SERVICE_UNSIGNED, SUBMIT_TX_SIGN_RESULT};
 use crate::query::ethereum::evm_chain_id;
 use crate::query::mining::{challenges, working_devices};
 use crate::submit::ethereum::{transact_unsigned, transact_unsigned_call_bytes};
 use crate::submit::mining::{im_online, register_device_with_ident};
-use crate::DeepSafeSubClient;
+use crate::{At, DeepSafeSubClient};
 use codec::Encode;
-use precompile_utils::prelude::UnboundedBytes;
-use precompile_utils::solidity::codec::Writer as EvmDataWriter;
-use sp_core::{H160, H256};
+use sp_core::{H256, U256};
 
-/// keccak_256("submitTxSignResult(bytes[],bytes[],uint256,uint256,bytes32,bytes[])".as_bytes())[..4]
-pub const REPORT_RESULT_SELECTOR: [u8; 4] = [118, 72, 134, 178];
-/// keccak_256("importNewTx(uint256,uint256,bytes[],uint256,bytes[],bytes[],bytes[],uint256)".as_bytes())[..4]
-pub const SUBMIT_TRANSACTION_SELECTOR: [u8; 4] = [58, 164, 61, 2];
-/// keccak_256("joinOrExitServiceUnsigned(bytes[],uint256,bytes[],bytes[])".as_bytes())[..4]
-pub const JOIN_OR_EXIT_SERVICE_UNSIGNED_SELECTOR: [u8; 4] = [99, 254, 70, 76];
+#[deprecated(note = "use 'precompile::SUBMIT_TX_SIGN_RESULT' instead")]
+pub const REPORT_RESULT_SELECTOR: [u8; 4] = SUBMIT_TX_SIGN_RESULT.method.selector;
+#[deprecated(note = "use 'precompile::IMPORT_NEW_TX' instead")]
+pub const SUBMIT_TRANSACTION_SELECTOR: [u8; 4] = crate::precompile::IMPORT_NEW_TX.method.selector;
+#[deprecated(note = "use 'precompile::JOIN_OR_EXIT_SERVICE_UNSIGNED' instead")]
+pub const JOIN_OR_EXIT_SERVICE_UNSIGNED_SELECTOR: [u8; 4] =
+    JOIN_OR_EXIT_SERVICE_UNSIGNED.method.selector;
 
 pub async fn call_register_v2(
     sub_client: &DeepSafeSubClient,
@@ -86,12 +87,12 @@ pub async fn query_session_and_challenge(
         version: did.0,
         pk: did.1,
     };
-    let (devices, session) = working_devices(sub_client, None, None)
+    let (devices, session) = working_devices(sub_client, None, At::Latest)
         .await
         .map_err(|e| e.to_string())?
         .ok_or("no working device".to_string())?;
     let res = if devices.contains(&(did, false)) {
-        match challenges(sub_client, session, None)
+        match challenges(sub_client, session, At::Latest)
             .await
             .map_err(|e| e.to_string())?
         {
@@ -114,51 +115,17 @@ pub async fn report_result_by_evm(
     signature: Vec<u8>,
     call_bytes: bool,
 ) -> Result<Vec<u8>, String> {
-    // build writer with 'reportResult' select
-    let writer = EvmDataWriter::new_with_selector(u32::from_be_bytes(REPORT_RESULT_SELECTOR))
-        .write(UnboundedBytes::from(pk))
-        .write(UnboundedBytes::from(sig))
-        .write(cid)
-        .write(fork_id)
-        .write(hash)
-        .write(UnboundedBytes::from(signature));
-
-    let input = writer.build();
-
-    let chain_id = evm_chain_id(sub_client, None)
+    let chain_id = evm_chain_id(sub_client, At::Latest)
         .await
         .map_err(|e| e.to_string())?
         .ok_or("get evm chain failed".to_string())?;
-    let tx = ethereum::EIP1559TransactionMessage {
-        chain_id,
-        nonce: sp_core::U256::from(0u128),
-        max_priority_fee_per_gas: sp_core::U256::from(1500000000u128),
-        max_fee_per_gas: sp_core::U256::from(4500000000u128),
-        gas_limit: sp_core::U256::from(50000000u128),
-        action: ethereum::TransactionAction::Call(H160::from_low_u64_be(1104)),
-        value: sp_core::U256::from(0u128),
-        input,
-        access_list: Default::default(),
-    };
-    let transaction = Transaction::EIP1559(EIP1559Transaction {
+    let mut tx = SUBMIT_TX_SIGN_RESULT.tx_message(
         chain_id,
-        nonce: crate::deepsafe::runtime_types::primitive_types::U256(tx.nonce.0),
-        max_priority_fee_per_gas: crate::deepsafe::runtime_types::primitive_types::U256(
-            tx.max_priority_fee_per_gas.0,
-        ),
-        max_fee_per_gas: crate::deepsafe::runtime_types::primitive_types::U256(
-            tx.max_fee_per_gas.0,
-        ),
-        gas_limit: crate::deepsafe::runtime_types::primitive_types::U256(tx.gas_limit.0),
-        // channel precompile contract address
-        action: TransactionAction::Call(H160::from_low_u64_be(1104)),
-        value: crate::deepsafe::runtime_types::primitive_types::U256(tx.value.0),
-        input: tx.input,
-        access_list: vec![],
-        odd_y_parity: Default::default(),
-        r: H256(Default::default()),
-        s: H256(Default::default()),
-    });
+        U256::zero(),
+        (pk, sig, cid, fork_id, hash, signature),
+    );
+    apply_client_fees(sub_client, None, &mut tx).await;
+    let transaction = unsigned_evm_transaction(tx);
 
     if call_bytes {
         transact_unsigned_call_bytes(sub_client, transaction).await
@@ -176,55 +143,44 @@ pub async fn join_or_exit_service_unsigned_by_evm(
     signature: Vec<u8>,
     purpose: Purpose,
 ) -> Result<String, String> {
-    // build writer with select
-    let writer = EvmDataWriter::new_with_selector(u32::from_be_bytes(
-        JOIN_OR_EXIT_SERVICE_UNSIGNED_SELECTOR,
-    ))
-    .write(UnboundedBytes::from(id))
-    .write(purpose as u8)
-    .write(UnboundedBytes::from(msg))
-    .write(UnboundedBytes::from(signature));
-
-    let input = writer.build();
-
-    let chain_id = evm_chain_id(sub_client, None)
+    let chain_id = evm_chain_id(sub_client, At::Latest)
         .await
         .map_err(|e| e.to_string())?
         .ok_or("get evm chain failed".to_string())?;
-    let tx = ethereum::EIP1559TransactionMessage {
+    let mut tx = JOIN_OR_EXIT_SERVICE_UNSIGNED.tx_message(
         chain_id,
-        nonce: sp_core::U256::from(0u128),
-        max_priority_fee_per_gas: sp_core::U256::from(1500000000u128),
-        max_fee_per_gas: sp_core::U256::from(4500000000u128),
-        gas_limit: sp_core::U256::from(50000000u128),
-        // mining precompile contract address
-        action: ethereum::TransactionAction::Call(H160::from_low_u64_be(1101)),
-        value: sp_core::U256::from(0u128),
-        input,
-        access_list: Default::default(),
+        U256::zero(),
+        (id, purpose as u8, msg, signature),
+    );
+    apply_client_fees(sub_client, None, &mut tx).await;
+    let transaction = unsigned_evm_transaction(tx);
+
+    transact_unsigned(sub_client, transaction)
+        .await
+        .map(|hash| "0x".to_string() + &hex::encode(hash.0))
+}
+
+// unsigned evm tx with zero signature, submitted by 'transact_unsigned'
+fn unsigned_evm_transaction(tx: ethereum::EIP1559TransactionMessage) -> Transaction {
+    use crate::deepsafe::runtime_types::primitive_types::U256 as RuntimeU256;
+    let action = match tx.action {
+        ethereum::TransactionAction::Call(address) => TransactionAction::Call(address),
+        ethereum::TransactionAction::Create => TransactionAction::Create,
     };
-    let transaction = Transaction::EIP1559(EIP1559Transaction {
-        chain_id,
-        nonce: crate::deepsafe::runtime_types::primitive_types::U256(tx.nonce.0),
-        max_priority_fee_per_gas: crate::deepsafe::runtime_types::primitive_types::U256(
-            tx.max_priority_fee_per_gas.0,
-        ),
-        max_fee_per_gas: crate::deepsafe::runtime_types::primitive_types::U256(
-            tx.max_fee_per_gas.0,
-        ),
-        gas_limit: crate::deepsafe::runtime_types::primitive_types::U256(tx.gas_limit.0),
-        action: TransactionAction::Call(H160::from_low_u64_be(1101)),
-        value: crate::deepsafe::runtime_types::primitive_types::U256(tx.value.0),
+    Transaction::EIP1559(EIP1559Transaction {
+        chain_id: tx.chain_id,
+        nonce: RuntimeU256(tx.nonce.0),
+        max_priority_fee_per_gas: RuntimeU256(tx.max_priority_fee_per_gas.0),
+        max_fee_per_gas: RuntimeU256(tx.max_fee_per_gas.0),
+        gas_limit: RuntimeU256(tx.gas_limit.0),
+        action,
+        value: RuntimeU256(tx.value.0),
         input: tx.input,
         access_list: vec![],
         odd_y_parity: Default::default(),
         r: H256(Default::default()),
         s: H256(Default::default()),
-    });
-
-    transact_unsigned(sub_client, transaction)
-        .await
-        .map(|hash| "0x".to_string() + &hex::encode(hash.0))
+    })
 }
 
 pub async fn query_current_block_number(sub_client: &DeepSafeSubClient) -> Result<u32, String> {